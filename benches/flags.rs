@@ -0,0 +1,144 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use enum_flags::enum_flags;
+use std::hint::black_box;
+
+#[repr(u32)]
+#[enum_flags]
+#[derive(Copy, Clone, PartialEq)]
+enum Flags {
+    None = 0,
+    A = 1,
+    B = 2,
+    C = 4,
+    D = 8,
+    E = 16,
+    F = 32,
+    G = 64,
+    H = 128,
+}
+
+#[repr(u8)]
+#[enum_flags]
+#[derive(Copy, Clone, PartialEq)]
+enum Small {
+    None = 0,
+    A = 1,
+    B = 2,
+    C = 4,
+    D = 8,
+    E = 16,
+    F = 32,
+    G = 64,
+    H = 128,
+}
+
+#[repr(u32)]
+#[enum_flags]
+#[derive(Copy, Clone, PartialEq)]
+enum Large {
+    None = 0,
+    Read = 1 << 0,
+    Write = 1 << 1,
+    Execute = 1 << 2,
+    Delete = 1 << 3,
+    Append = 1 << 4,
+    Create = 1 << 5,
+    Rename = 1 << 6,
+    Lock = 1 << 7,
+    Unlock = 1 << 8,
+    Share = 1 << 9,
+    Hidden = 1 << 10,
+    System = 1 << 11,
+    Archive = 1 << 12,
+    Compressed = 1 << 13,
+    Encrypted = 1 << 14,
+    Temporary = 1 << 15,
+    Offline = 1 << 16,
+    Sparse = 1 << 17,
+    Indexed = 1 << 18,
+    Pinned = 1 << 19,
+    Unpinned = 1 << 20,
+    Recall = 1 << 21,
+    Integrity = 1 << 22,
+    NoScrub = 1 << 23,
+    Virtual = 1 << 24,
+    Device = 1 << 25,
+    Normal = 1 << 26,
+    Directory = 1 << 27,
+    Link = 1 << 28,
+    Mounted = 1 << 29,
+    Synced = 1 << 30,
+    Backup = 1 << 31,
+}
+
+fn bench_contains(c: &mut Criterion) {
+    let flags = Flags::A | Flags::C | Flags::E | Flags::G;
+    c.bench_function("contains", |b| {
+        b.iter(|| black_box(flags).contains(black_box(Flags::A | Flags::E)))
+    });
+}
+
+fn bench_insert(c: &mut Criterion) {
+    c.bench_function("insert", |b| {
+        b.iter(|| {
+            let mut flags = black_box(Flags::A);
+            flags.insert(black_box(Flags::H));
+            flags
+        })
+    });
+}
+
+fn bench_iter(c: &mut Criterion) {
+    let flags = Flags::A | Flags::C | Flags::E | Flags::G;
+    c.bench_function("iter", |b| b.iter(|| black_box(flags).iter().count()));
+}
+
+fn bench_from_bits(c: &mut Criterion) {
+    c.bench_function("from_bits", |b| b.iter(|| Flags::from_bits(black_box(0x55))));
+}
+
+fn bench_from_num_u8(c: &mut Criterion) {
+    c.bench_function("from_num_u8", |b| {
+        b.iter(|| {
+            for n in 0..=u8::MAX {
+                black_box(Small::from_bits_retain(black_box(n)));
+            }
+        })
+    });
+}
+
+fn bench_ops_u8(c: &mut Criterion) {
+    c.bench_function("ops_u8", |b| {
+        b.iter(|| {
+            let mut flags = Small::None;
+            for n in 0..=u8::MAX {
+                let other = black_box(Small::from_bits_retain(n));
+                flags = (flags | other) & !Small::D ^ Small::A;
+            }
+            flags.bits()
+        })
+    });
+}
+
+fn bench_from_name(c: &mut Criterion) {
+    let names = Flags::names();
+    c.bench_function("from_name", |b| {
+        b.iter(|| names.iter().filter_map(|x| Flags::from_name(black_box(x))).count())
+    });
+    let names = Large::names();
+    c.bench_function("from_name_large", |b| {
+        b.iter(|| names.iter().filter_map(|x| Large::from_name(black_box(x))).count())
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_contains,
+    bench_insert,
+    bench_iter,
+    bench_from_bits,
+    bench_from_num_u8,
+    bench_ops_u8,
+    bench_from_name
+);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Ident, Meta, NestedMeta};
+
+use crate::to_snake_case;
+
+/// Generates a getter and a setter on the struct for every flag listed in a
+/// `#[flags(...)]` field attribute, e.g. `perms_read()` and `set_perms_read(bool)`.
+///
+/// The derive can't see the variants of the flags type, so the flags have to be listed.
+pub(crate) fn impl_accessors(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let struct_name = &ast.ident;
+    let vis = &ast.vis;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = match &ast.data {
+        Data::Struct(data_struct) => &data_struct.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "`FlagsAccessors` has to be used with structs",
+            ))
+        }
+    };
+
+    let mut methods = Vec::new();
+
+    for field in fields {
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("flags")) {
+            let field_name = field.ident.as_ref().ok_or_else(|| {
+                syn::Error::new_spanned(field, "`#[flags]` has to be used on named fields")
+            })?;
+            let ty = &field.ty;
+
+            let flags = match attr.parse_meta()? {
+                Meta::List(list) => list
+                    .nested
+                    .iter()
+                    .map(|nested| match nested {
+                        NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                            Ok(path.get_ident().unwrap().clone())
+                        }
+                        _ => Err(syn::Error::new_spanned(nested, "expected a flag name")),
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?,
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "list the flags to generate accessors for, e.g. `#[flags(Read, Write)]`",
+                    ))
+                }
+            };
+
+            for flag in flags {
+                let snake = to_snake_case(&flag.to_string());
+                let getter = Ident::new(&format!("{}_{}", field_name, snake), flag.span());
+                let setter = Ident::new(&format!("set_{}_{}", field_name, snake), flag.span());
+                let getter_doc = format!("Returns `true` if the `{}` flag of `{}` is set.", flag, field_name);
+                let setter_doc = format!("Sets or unsets the `{}` flag of `{}`.", flag, field_name);
+
+                methods.push(quote! {
+                    #[doc = #getter_doc]
+                    #[inline]
+                    #vis fn #getter(&self) -> bool {
+                        self.#field_name.contains(<#ty>::#flag)
+                    }
+
+                    #[doc = #setter_doc]
+                    #[inline]
+                    #vis fn #setter(&mut self, value: bool) {
+                        self.#field_name.set(<#ty>::#flag, value)
+                    }
+                });
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
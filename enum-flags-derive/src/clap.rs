@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Path, Visibility};
+
+/// Implements `clap::ValueEnum`, so that every named flag is a possible value of an argument,
+/// e.g. `--flag A --flag C`. The values of a repeated argument are combined with `sum()`.
+pub(crate) fn impl_clap(krate: &Path, vis: &Visibility, enum_name: &Ident) -> TokenStream {
+    let clap = quote!(#krate::__private::clap);
+
+    quote! {
+        impl #enum_name {
+            /// Returns the names of the named flags as clap possible values,
+            /// e.g. for `clap::builder::PossibleValuesParser`.
+            #vis fn possible_values() -> impl core::iter::Iterator<Item = #clap::builder::PossibleValue> {
+                Self::names().iter().map(|name| #clap::builder::PossibleValue::new(*name))
+            }
+        }
+
+        impl #clap::ValueEnum for #enum_name {
+            fn value_variants<'a>() -> &'a [Self] {
+                Self::variants()
+            }
+
+            fn to_possible_value(&self) -> core::option::Option<#clap::builder::PossibleValue> {
+                Self::index_of(Self::from_num(self.as_num()))
+                    .map(|i| #clap::builder::PossibleValue::new(Self::names()[i]))
+            }
+        }
+    }
+}
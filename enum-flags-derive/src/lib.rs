@@ -0,0 +1,2483 @@
+//!
+//! Procedural macro implementation for [enum-flags](https://docs.rs/enum-flags).
+//!
+//! This crate is an implementation detail, depend on `enum-flags` instead.
+
+extern crate proc_macro;
+
+#[cfg(feature = "accessors")]
+mod accessors;
+#[cfg(feature = "clap")]
+mod clap;
+mod serde;
+
+use syn::{
+    AttrStyle, Attribute, Data, Expr, ExprLit, Fields, Ident, Lit, LitInt, Meta, NestedMeta, Path,
+};
+use {
+    self::proc_macro::TokenStream,
+    proc_macro2::{self, Span},
+    quote::*,
+    syn::{parse_macro_input, DeriveInput},
+};
+
+use crate::serde::SerdeMode;
+
+#[proc_macro_attribute]
+pub fn enum_flags(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as Args);
+    let ast = parse_macro_input!(input as DeriveInput);
+    Options::parse(args)
+        .and_then(|options| impl_flags(ast, options))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[cfg(feature = "accessors")]
+#[proc_macro_derive(FlagsAccessors, attributes(flags))]
+pub fn flags_accessors(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    accessors::impl_accessors(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Options passed to the attribute, e.g. `#[enum_flags(no_copy)]`.
+#[derive(Default)]
+struct Options {
+    /// Don't derive `Copy` automatically.
+    no_copy: bool,
+    /// Don't generate the per-flag `has_*` methods.
+    no_accessors: bool,
+    /// Generate per-flag methods returning `Option<Self>`, e.g. `a()`, instead of `has_*`.
+    option_accessors: bool,
+    /// Match flag names ignoring ASCII case when parsing.
+    case_insensitive: bool,
+    /// Display a value with every flag set as this instead of listing the flags.
+    display_all: Option<String>,
+    /// Implement `Serialize` and `Deserialize` with the given representation.
+    serde: Option<SerdeMode>,
+    /// The path to the `enum_flags` crate, for when it's only reachable through a re-export.
+    krate: Option<Path>,
+    /// Fail if the flags fit in a narrower repr than the declared one.
+    strict_repr: bool,
+    /// Fail unless the discriminants are ascending powers of two.
+    ordered: bool,
+    /// Let `FromStr` parse `!`, `&` and `-` as well as `|`, e.g. `"A & !B"`.
+    parse_ops: bool,
+    /// The flags `Default` returns, e.g. `"A | C"`, rather than the empty value.
+    default: Option<syn::LitStr>,
+    /// Append the raw value to the `Debug` output with this format string, e.g. `" = {:#x}"`.
+    debug_num: Option<&'static str>,
+    /// Generate a `<Name>Mask` companion type for masks applied to values.
+    with_mask: bool,
+    /// Prefix the names `Debug` prints with the module path, e.g. `my_mod::Flags::A`.
+    debug_path: bool,
+    /// Named masks declared with `group(name = [A, B])`, generated as `const NAME: Self`.
+    groups: Vec<(Ident, Vec<Ident>)>,
+}
+
+/// The `enum_flags` arguments: the usual meta items, plus `group(...)`, whose
+/// `name = [A, B]` entries aren't valid meta items.
+struct Args {
+    metas: Vec<NestedMeta>,
+    groups: Vec<(Ident, Vec<Ident>)>,
+}
+
+impl syn::parse::Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Args { metas: Vec::new(), groups: Vec::new() };
+        while !input.is_empty() {
+            let is_group = input.peek(Ident) && input.peek2(syn::token::Paren);
+            if is_group && input.fork().parse::<Ident>()? == "group" {
+                input.parse::<Ident>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                while !content.is_empty() {
+                    let name: Ident = content.parse()?;
+                    content.parse::<syn::Token![=]>()?;
+                    let flags;
+                    syn::bracketed!(flags in content);
+                    let flags = flags.parse_terminated::<Ident, syn::Token![,]>(Ident::parse)?;
+                    args.groups.push((name, flags.into_iter().collect()));
+                    if !content.is_empty() {
+                        content.parse::<syn::Token![,]>()?;
+                    }
+                }
+            } else {
+                args.metas.push(input.parse()?);
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl Options {
+    fn parse(args: Args) -> syn::Result<Options> {
+        let mut options = Options { groups: args.groups, ..Options::default() };
+        for arg in &args.metas {
+            match arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("no_copy") => {
+                    options.no_copy = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("no_accessors") => {
+                    options.no_accessors = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("case_insensitive") => {
+                    options.case_insensitive = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("strict_repr") => {
+                    options.strict_repr = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("ordered") => {
+                    options.ordered = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("parse_ops") => {
+                    options.parse_ops = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("display_all") => {
+                    match &nv.lit {
+                        Lit::Str(s) => options.display_all = Some(s.value()),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("with_mask") => {
+                    options.with_mask = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("debug_path") => {
+                    options.debug_path = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("debug_num") => {
+                    options.debug_num = Some(" = {}");
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("debug_num") => {
+                    match &nv.lit {
+                        Lit::Str(s) if s.value() == "hex" => options.debug_num = Some(" = {:#x}"),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected `\"hex\"`")),
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("accessor_kind") => {
+                    match &nv.lit {
+                        Lit::Str(s) if s.value() == "bool" => options.option_accessors = false,
+                        Lit::Str(s) if s.value() == "option" => options.option_accessors = true,
+                        lit => return Err(syn::Error::new_spanned(lit, "expected `\"bool\"` or `\"option\"`")),
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("default") => {
+                    match &nv.lit {
+                        Lit::Str(s) => options.default = Some(s.clone()),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("crate") => {
+                    match &nv.lit {
+                        Lit::Str(s) => options.krate = Some(s.parse()?),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a path string")),
+                    }
+                }
+                NestedMeta::Meta(meta) if meta.path().is_ident("serde") => {
+                    if !cfg!(feature = "serde") {
+                        return Err(syn::Error::new_spanned(
+                            arg,
+                            "the `serde` feature of `enum-flags` has to be enabled",
+                        ));
+                    }
+                    options.serde = Some(SerdeMode::parse(meta)?);
+                }
+                _ => return Err(syn::Error::new_spanned(arg, "unknown `enum_flags` option")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn impl_flags(mut ast: DeriveInput, options: Options) -> syn::Result<proc_macro2::TokenStream> {
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.generics,
+            "`enum_flags` enums can't be generic",
+        ));
+    }
+    if let Some(ref where_clause) = ast.generics.where_clause {
+        return Err(syn::Error::new_spanned(
+            where_clause,
+            "`enum_flags` enums can't have a where clause",
+        ));
+    }
+
+    let enum_name = &ast.ident;
+
+    let has_repr = extract_repr(&ast.attrs).is_some();
+    let num = if let Some(repr) = extract_repr(&ast.attrs) {
+        repr
+    } else {
+        ast.attrs.push(Attribute {
+            pound_token: Default::default(),
+            style: AttrStyle::Outer,
+            bracket_token: Default::default(),
+            path: Path::from(syn::Ident::new("repr", Span::call_site())),
+            tokens: syn::parse2(quote! { (usize) }).unwrap(),
+        });
+        syn::Ident::new("usize", Span::call_site())
+    };
+
+    let vis = &ast.vis;
+
+    let krate = options
+        .krate
+        .clone()
+        .unwrap_or_else(|| syn::parse_quote!(::enum_flags));
+
+    // the names given with `#[flag(rename = "...")]`, the others keep the identifier
+    let mut renames: Vec<(Ident, syn::LitStr)> = Vec::new();
+
+    match &mut ast.data {
+        Data::Enum(ref mut data_enum) => {
+            let max = max_value(&num);
+            let mut seen: Vec<(u128, Ident)> = Vec::new();
+            let mut i = 0;
+            let mut after_expr = false;
+            let mut has_expr = false;
+
+            for variant in &mut data_enum.variants {
+                if let Some(name) = take_rename(&mut variant.attrs)? {
+                    renames.push((variant.ident.clone(), name));
+                }
+
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        &variant.fields,
+                        "`enum_flags` variants must be fieldless, e.g. `A = 1`",
+                    ));
+                }
+
+                let value = match variant.discriminant {
+                    Some((_, Expr::Lit(ExprLit { lit: Lit::Int(ref lit_int), .. }))) => {
+                        lit_int.base10_parse::<u128>()?
+                    }
+                    // a const expression, e.g. `A = BIT_A`, is checked by the generated code,
+                    // and the next variant can't count on from it
+                    Some(_) => {
+                        after_expr = true;
+                        has_expr = true;
+                        continue;
+                    }
+                    None if after_expr => {
+                        return Err(syn::Error::new_spanned(
+                            &*variant,
+                            format!(
+                                "`{}` needs a discriminant, there's no counting on from a const expression",
+                                variant.ident
+                            ),
+                        ));
+                    }
+                    None => {
+                        variant.discriminant = Some((
+                            syn::token::Eq(Span::call_site()),
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Int(LitInt::new(i.to_string().as_str(), Span::call_site())),
+                                attrs: vec![],
+                            }),
+                        ));
+                        i
+                    }
+                };
+                after_expr = false;
+
+                if value > max {
+                    return Err(syn::Error::new_spanned(
+                        &*variant,
+                        format!("discriminant `{}` doesn't fit in `{}`", value, num),
+                    ));
+                }
+
+                if variant.ident == "None" && value != 0 {
+                    return Err(syn::Error::new_spanned(
+                        &*variant,
+                        format!("`None` has to be `0`, it's the empty value, not `{}`", value),
+                    ));
+                }
+
+                if let Some((_, other)) = seen.iter().find(|(v, _)| *v == value) {
+                    return Err(syn::Error::new_spanned(
+                        &*variant,
+                        format!("`{}` has the same value as `{}`", variant.ident, other),
+                    ));
+                }
+
+                if options.ordered && value != 0 {
+                    if !value.is_power_of_two() {
+                        return Err(syn::Error::new_spanned(
+                            &*variant,
+                            format!("`{}` is `{}`, `ordered` flags have to be single bits", variant.ident, value),
+                        ));
+                    }
+                    // the zero variant may sit anywhere, it isn't a bit
+                    let largest = seen.iter().filter(|(v, _)| *v != 0).max_by_key(|(v, _)| *v);
+                    if let Some((_, previous)) = largest.filter(|(v, _)| *v > value) {
+                        return Err(syn::Error::new_spanned(
+                            &*variant,
+                            format!("`{}` comes after the larger `{}`, `ordered` flags have to be ascending", variant.ident, previous),
+                        ));
+                    }
+                }
+
+                seen.push((value, variant.ident.clone()));
+                i = value.saturating_add(1);
+            }
+
+            // a renamed flag can't take the name of another one
+            for (ident, name) in &renames {
+                let taken = data_enum.variants.iter().map(|v| &v.ident).find(|other| {
+                    *other != ident
+                        && match renames.iter().find(|(x, _)| x == *other) {
+                            Some((_, other_name)) => other_name.value() == name.value(),
+                            None => **other == name.value(),
+                        }
+                });
+                if let Some(other) = taken {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        format!("`{}` can't be renamed to `{}`, it's the name of `{}`", ident, name.value(), other),
+                    ));
+                }
+            }
+
+            // `parse_ops` splits on the operators before looking the names up
+            if options.parse_ops {
+                if let Some((ident, name)) = renames.iter().find(|(_, name)| name.value().contains(['|', '&', '-', '!'])) {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        format!(
+                            "`{}` can't be renamed to `{}` with `parse_ops`, which takes `|`, `&`, `-` and `!` as operators",
+                            ident,
+                            name.value()
+                        ),
+                    ));
+                }
+            }
+
+            if options.strict_repr {
+                let largest = seen.iter().map(|(v, _)| *v).max().unwrap_or(0);
+                if let Some(repr) = narrower_repr(&num, largest) {
+                    let message = format!("the flags fit in `{}`, use `#[repr({})]` instead of `{}`", repr, repr, num);
+                    return Err(match ast.attrs.iter().find(|attr| attr.path.is_ident("repr")) {
+                        Some(attr) if has_repr => syn::Error::new_spanned(attr, message),
+                        _ => syn::Error::new_spanned(&ast.ident, message),
+                    });
+                }
+            }
+
+            // `__Composed__` takes a value none of the flags has, counting on from the last
+            // flag would overflow after e.g. `All = 0xFF`, or hit an earlier flag after `B = 1`
+            let composed_discriminant = if has_expr {
+                // only the compiler knows the values of the const expressions
+                let values = data_enum.variants.iter().map(|v| &v.discriminant.as_ref().expect("").1);
+                let count = data_enum.variants.len();
+                quote! {{
+                    const VALUES: [#num; #count] = [#(#values),*];
+                    let mut n: #num = 0;
+                    loop {
+                        let mut i = 0;
+                        while i < #count && VALUES[i] != n {
+                            i += 1;
+                        }
+                        if i == #count {
+                            break n;
+                        }
+                        if n == #num::MAX {
+                            panic!("no room left for the composed variant");
+                        }
+                        n += 1;
+                    }
+                }}
+            } else {
+                match (0..=max).find(|n| seen.iter().all(|(v, _)| v != n)) {
+                    Some(n) => LitInt::new(n.to_string().as_str(), Span::call_site()).to_token_stream(),
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            &ast.ident,
+                            format!("no room left for the composed variant, every `{}` value is a flag", num),
+                        ));
+                    }
+                }
+            };
+            let mut composed: syn::Variant = syn::parse2(quote! {__Composed__(#num)}).unwrap();
+            composed.discriminant = Some((syn::token::Eq(Span::call_site()), Expr::Verbatim(composed_discriminant)));
+            data_enum.variants.push(composed);
+        }
+        Data::Struct(ref data_struct) => {
+            return Err(syn::Error::new_spanned(
+                data_struct.struct_token,
+                "`enum_flags` has to be used with enums",
+            ));
+        }
+        Data::Union(ref data_union) => {
+            return Err(syn::Error::new_spanned(
+                data_union.union_token,
+                "`enum_flags` has to be used with enums",
+            ));
+        }
+    }
+
+    let user_derives = extract_derives(&ast.attrs);
+
+    // `PartialEq` is implemented below on the raw bits, a derived one would compare
+    // the variants, so that `A` and `__Composed__(1)` are different.
+    strip_derive(&mut ast.attrs, "PartialEq");
+    // same for `Hash`, which has to agree with `PartialEq`
+    strip_derive(&mut ast.attrs, "Hash");
+
+    // try to derive Copy,Clone automatically
+    {
+        let dervies = ["Copy", "Clone"]
+            .iter()
+            .filter(|x| !(options.no_copy && **x == "Copy"))
+            .filter(|x| user_derives.iter().all(|d| d.ne(x)))
+            .map(|x| Ident::new(x, Span::call_site()))
+            .collect::<Vec<_>>();
+
+        if !dervies.is_empty() {
+            ast.attrs.push(Attribute {
+                pound_token: Default::default(),
+                style: AttrStyle::Outer,
+                bracket_token: Default::default(),
+                path: Path::from(syn::Ident::new("derive", Span::call_site())),
+                tokens: syn::parse2(quote! { (#(#dervies),* )}).unwrap(),
+            });
+        }
+    }
+
+    let result = match &ast.data {
+        Data::Enum(ref data_enum) => {
+            let (enum_items, enum_values): (Vec<&syn::Ident>, Vec<&syn::Expr>) = data_enum
+                .variants
+                .iter()
+                .filter(|f| f.ident.ne("__Composed__"))
+                .map(|v| (&v.ident, &v.discriminant.as_ref().expect("").1))
+                .unzip();
+
+            // the named flags, i.e. every variant except the zero one
+            let flag_items = enum_items
+                .iter()
+                .zip(&enum_values)
+                .filter(|(_, v)| discriminant_value(v) != Some(0))
+                .map(|(x, _)| *x)
+                .collect::<Vec<_>>();
+
+            // whether a const expression, e.g. `A = BIT_A`, is a flag rather than the zero
+            // variant, which only the compiler knows
+            let nonzero_of = |x: &Ident| quote!((#enum_name::#x).as_num() != 0);
+            let flag_conds = flag_items
+                .iter()
+                .zip(enum_values.iter().filter(|v| discriminant_value(v) != Some(0)))
+                .map(|(x, v)| discriminant_value(v).map_or_else(|| Some(nonzero_of(x)), |_| None))
+                .collect::<Vec<_>>();
+
+            let known_count = flag_conds.iter().filter(|c| c.is_none()).count();
+            let unknown_conds = flag_conds.iter().flatten();
+            let flag_count = quote!(#known_count #(+ (#unknown_conds) as usize)*);
+
+            // an array with an element per named flag, in which a const expression that
+            // turns out to be zero is left out and the following flags move up
+            let flag_array = |ty: proc_macro2::TokenStream, fill: proc_macro2::TokenStream, elems: Vec<proc_macro2::TokenStream>| {
+                if flag_conds.iter().all(Option::is_none) {
+                    return quote!([#(#elems),*]);
+                }
+                let mut pos = quote!(0usize);
+                let mut assigns = Vec::new();
+                for (cond, elem) in flag_conds.iter().zip(elems) {
+                    match cond {
+                        None => {
+                            assigns.push(quote!(flags[#pos] = #elem;));
+                            pos = quote!(#pos + 1);
+                        }
+                        Some(cond) => {
+                            assigns.push(quote!(if #cond { flags[#pos] = #elem; }));
+                            pos = quote!(#pos + (#cond) as usize);
+                        }
+                    }
+                }
+                quote! {{
+                    const FILL: #ty = #fill;
+                    let mut flags = [FILL; #flag_count];
+                    #(#assigns)*
+                    flags
+                }}
+            };
+
+            // the checks of the variant loop which a const expression is left out of
+            let mut discriminant_checks = Vec::new();
+            for (x, v) in enum_items.iter().zip(&enum_values) {
+                if discriminant_value(v).is_some() {
+                    continue;
+                }
+                if *x == "None" {
+                    discriminant_checks.push(quote_spanned! {Ident::span(x)=>
+                        const _: () = assert!((#enum_name::None).as_num() == 0, "`None` has to be `0`, it's the empty value");
+                    });
+                }
+                if options.ordered {
+                    let message = format!("`{}` isn't a single bit, `ordered` flags have to be single bits", x);
+                    discriminant_checks.push(quote_spanned! {Ident::span(x)=>
+                        const _: () = assert!(
+                            (#enum_name::#x).as_num() == 0 || (#enum_name::#x).as_num().count_ones() == 1,
+                            #message
+                        );
+                    });
+                }
+            }
+            if options.ordered {
+                for (j, (x, v)) in enum_items.iter().zip(&enum_values).enumerate() {
+                    for (previous, pv) in enum_items.iter().zip(&enum_values).take(j) {
+                        if discriminant_value(v).is_some() && discriminant_value(pv).is_some() {
+                            continue;
+                        }
+                        // widened with the sign, which keeps the order of the repr's bits
+                        let message = format!("`{}` comes after the larger `{}`, `ordered` flags have to be ascending", x, previous);
+                        discriminant_checks.push(quote_spanned! {Ident::span(x)=>
+                            const _: () = assert!({
+                                let (previous, n) = ((#enum_name::#previous).as_num() as u128, (#enum_name::#x).as_num() as u128);
+                                previous == 0 || n == 0 || previous < n
+                            }, #message);
+                        });
+                    }
+                }
+            }
+
+            // const expressions which aren't paths can't be patterns, so they're matched in a guard
+            let from_num_arms = enum_values
+                .iter()
+                .map(|v| match v {
+                    Expr::Lit(_) | Expr::Path(_) => quote!(#v),
+                    _ => quote!(n if n == #v),
+                })
+                .collect::<Vec<_>>();
+
+            let name_of = |x: &Ident| {
+                renames
+                    .iter()
+                    .find(|(ident, _)| ident == x)
+                    .map_or_else(|| x.to_string(), |(_, name)| name.value())
+            };
+
+            let flag_item_strs = flag_items
+                .iter()
+                .map(|x| name_of(x))
+                .collect::<Vec<String>>();
+
+            let has_enum_items = enum_items
+                .iter()
+                .map(|x| {
+                    let mut n = to_snake_case(&x.to_string());
+                    n.insert_str(0, "has_");
+                    Ident::new(n.as_str(), enum_name.span())
+                })
+                .collect::<Vec<syn::Ident>>();
+
+            let has_enum_docs = enum_items
+                .iter()
+                .zip(&enum_values)
+                .map(|(x, v)| {
+                    if discriminant_value(v) == Some(0) {
+                        format!("Returns `true` if no flag is set, i.e. the value is `{}`.", x)
+                    } else if discriminant_value(v).is_none() {
+                        format!(
+                            "Returns `true` if the `{}` flag (`{}`) is set, or if no flag is set when it's zero.",
+                            x,
+                            v.to_token_stream()
+                        )
+                    } else {
+                        format!(
+                            "Returns `true` if the `{}` flag (`{}`) is set.",
+                            x,
+                            v.to_token_stream()
+                        )
+                    }
+                })
+                .collect::<Vec<String>>();
+
+            // every value contains the zero variant, so its accessor checks for emptiness
+            let has_enum_bodies = enum_items
+                .iter()
+                .zip(&enum_values)
+                .map(|(x, v)| match discriminant_value(v) {
+                    Some(0) => quote!(self.is_empty()),
+                    Some(_) => quote!(self.contains(#enum_name::#x)),
+                    None => quote! {
+                        if (#enum_name::#x).as_num() == 0 {
+                            self.is_empty()
+                        } else {
+                            self.contains(#enum_name::#x)
+                        }
+                    },
+                })
+                .collect::<Vec<_>>();
+
+            // a deprecated variant deprecates its accessor as well
+            let has_enum_deprecations = data_enum
+                .variants
+                .iter()
+                .filter(|f| f.ident.ne("__Composed__"))
+                .map(|v| v.attrs.iter().filter(|attr| attr.path.is_ident("deprecated")).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+
+            // the empty value is whichever variant is zero, whatever it's called,
+            // unless the `default` option lists the flags
+            let default_flags = match &options.default {
+                Some(lit) => {
+                    let value = lit.value();
+                    let mut flags = Vec::new();
+                    for name in value.split('|').map(str::trim).filter(|name| !name.is_empty()) {
+                        match enum_items.iter().find(|x| **x == name) {
+                            Some(x) => flags.push(*x),
+                            None => {
+                                return Err(syn::Error::new_spanned(
+                                    lit,
+                                    format!("`{}` isn't a variant of `{}`", name, enum_name),
+                                ))
+                            }
+                        }
+                    }
+                    flags
+                }
+                None => Vec::new(),
+            };
+            // every group becomes an uppercased const, e.g. `group(io = [Read, Write])` is `IO`
+            let mut group_consts = Vec::new();
+            for (name, flags) in &options.groups {
+                if let Some(flag) = flags.iter().find(|x| !enum_items.contains(x)) {
+                    return Err(syn::Error::new_spanned(
+                        flag,
+                        format!("`{}` isn't a variant of `{}`", flag, enum_name),
+                    ));
+                }
+                let members = flags.iter().map(|x| format!("`{}`", x)).collect::<Vec<_>>();
+                let doc = format!(" The `{}` group: {}.", name, members.join(", "));
+                let const_name = Ident::new(&name.to_string().to_uppercase(), name.span());
+                let taken_by = if GENERATED_CONSTS.iter().any(|x| const_name == x) {
+                    Some("a generated const".to_string())
+                } else if enum_items.contains(&&const_name) {
+                    Some(format!("the variant `{}`", const_name))
+                } else {
+                    options
+                        .groups
+                        .iter()
+                        .take_while(|(other, _)| other != name)
+                        .find(|(other, _)| const_name == other.to_string().to_uppercase())
+                        .map(|(other, _)| format!("the `{}` group", other))
+                };
+                if let Some(taken_by) = taken_by {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        format!("the `{}` group would be `{}::{}`, which is taken by {}", name, enum_name, const_name, taken_by),
+                    ));
+                }
+                group_consts.push(quote! {
+                    #[doc = #doc]
+                    #vis const #const_name: Self = Self::from_num(0 #(| #enum_name::#flags.as_num())*);
+                });
+            }
+
+            let default_impl = if user_derives.iter().any(|d| d == "Default") {
+                if let Some(lit) = &options.default {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "the `default` option conflicts with `#[derive(Default)]`",
+                    ));
+                }
+                quote! {}
+            } else {
+                quote! {
+                    impl core::default::Default for #enum_name {
+                        #[inline]
+                        fn default() -> Self {
+                            Self::from_num(0 #(| #enum_name::#default_flags.as_num())*)
+                        }
+                    }
+                }
+            };
+
+            let serde_impl = match options.serde {
+                Some(mode) => crate::serde::impl_serde(&krate, enum_name, &num, mode),
+                None => quote! {},
+            };
+
+            #[cfg(feature = "clap")]
+            let clap_impl = crate::clap::impl_clap(&krate, vis, enum_name);
+            #[cfg(not(feature = "clap"))]
+            let clap_impl = quote! {};
+
+            let accessors = if options.no_accessors {
+                quote! {}
+            } else if options.option_accessors {
+                // the zero variant is left out, `is_empty` covers it
+                let (items, deprecations): (Vec<_>, Vec<_>) = enum_items
+                    .iter()
+                    .zip(&enum_values)
+                    .zip(&has_enum_deprecations)
+                    .filter(|((_, v), _)| discriminant_value(v) != Some(0))
+                    .map(|((x, _), d)| (*x, d))
+                    .unzip();
+                // a const expression which is zero is never `Some`, like the zero variant
+                let conds = items.iter().zip(&flag_conds).map(|(x, cond)| match cond {
+                    Some(cond) => quote!(#cond && self.contains(#enum_name::#x)),
+                    None => quote!(self.contains(#enum_name::#x)),
+                });
+                let mut getters: Vec<(Ident, &Ident)> = Vec::new();
+                for x in &items {
+                    let n = to_snake_case(&x.to_string());
+                    if ["self", "super", "crate"].contains(&n.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            x,
+                            format!("`{}` can't have a getter, `{}` can't be the name of a method", x, n),
+                        ));
+                    }
+                    // a getter can't take the name of another method, e.g. `Set` would clash with `set`
+                    if INHERENT_METHODS.contains(&n.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            x,
+                            format!("`{}` can't have a getter, `{}` is already a method of `{}`", x, n, enum_name),
+                        ));
+                    }
+                    // a keyword, e.g. `Type`, needs to be a raw identifier
+                    let getter = match syn::parse_str::<Ident>(&n) {
+                        Ok(_) => Ident::new(&n, enum_name.span()),
+                        Err(_) => Ident::new_raw(&n, enum_name.span()),
+                    };
+                    if let Some((_, other)) = getters.iter().find(|(g, _)| *g == getter) {
+                        return Err(syn::Error::new_spanned(
+                            x,
+                            format!("`{}` can't have a getter, `{}` is already the getter of `{}`", x, n, other),
+                        ));
+                    }
+                    getters.push((getter, x));
+                }
+                let getters = getters.iter().map(|(getter, _)| getter);
+                let docs = items
+                    .iter()
+                    .map(|x| format!("Returns `Some({})` if the `{}` flag is set, `None` otherwise.", x, x));
+                quote! {
+                    #(
+                        #[doc = #docs]
+                        #(#deprecations)*
+                        #[inline]
+                        #vis fn #getters(&self) -> core::option::Option<Self> {
+                            if #conds {
+                                core::option::Option::Some(#enum_name::#items)
+                            } else {
+                                core::option::Option::None
+                            }
+                        }
+                    )*
+                }
+            } else {
+                quote! {
+                    #(
+                        #[doc = #has_enum_docs]
+                        #(#has_enum_deprecations)*
+                        #[inline]
+                        #vis fn #has_enum_items(&self)-> bool {
+                            #has_enum_bodies
+                        }
+                    )*
+                }
+            };
+
+            // Debug writes the opening parenthesis with the first flag and the separator
+            // with every following one, so that each flag is a single `write_str`
+            // with `debug_path` every name is prefixed with the module path at the definition
+            let debug_str = |prefix: &str, name: String| {
+                if options.debug_path {
+                    quote!(concat!(#prefix, module_path!(), "::", #name))
+                } else {
+                    let s = format!("{}{}", prefix, name);
+                    quote!(#s)
+                }
+            };
+            let (debug_firsts, debug_rests): (Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>) = flag_items
+                .iter()
+                .map(|x| {
+                    let name = format!("{}::{}", enum_name, x);
+                    (debug_str("(", name.clone()), debug_str(" | ", name))
+                })
+                .unzip();
+            let debug_firsts = flag_array(quote!(&str), quote!(""), debug_firsts);
+            let debug_rests = flag_array(quote!(&str), quote!(""), debug_rests);
+
+            let enum_name_str = enum_name.to_string();
+
+            let enum_item_strs = enum_items
+                .iter()
+                .map(|x| name_of(x))
+                .collect::<Vec<String>>();
+
+            let from_name = if options.case_insensitive {
+                quote! {
+                    /// Returns the variant declared as `name`, ignoring ASCII case, if any.
+                    #vis fn from_name(name: &str) -> core::option::Option<Self> {
+                        #(
+                            if name.eq_ignore_ascii_case(#enum_item_strs) {
+                                return core::option::Option::Some(#enum_name::#enum_items);
+                            }
+                        )*
+                        core::option::Option::None
+                    }
+                }
+            } else {
+                quote! {
+                    /// Returns the variant declared as `name`, if any.
+                    #vis fn from_name(name: &str) -> core::option::Option<Self> {
+                        match name {
+                            #(
+                                #enum_item_strs => core::option::Option::Some(#enum_name::#enum_items),
+                            )*
+                            _ => core::option::Option::None
+                        }
+                    }
+                }
+            };
+
+            // the variant printed for the empty value, if there is a zero variant,
+            // a const expression is one if the compiler finds it's zero
+            let empty_item = enum_items
+                .iter()
+                .zip(&enum_values)
+                .find(|(_, v)| discriminant_value(v) == Some(0))
+                .map(|(x, _)| *x);
+            let (empty_name, debug_empty) = match empty_item {
+                Some(x) => {
+                    let name = name_of(x);
+                    (quote!(#name), debug_str("(", format!("{}::{}", enum_name, x)))
+                }
+                None => enum_items
+                    .iter()
+                    .zip(&enum_values)
+                    .filter(|(_, v)| discriminant_value(v).is_none())
+                    .rev()
+                    .fold((quote!(""), quote!("(")), |(name_else, debug_else), (x, _)| {
+                        let name = name_of(x);
+                        let debug = debug_str("(", format!("{}::{}", enum_name, x));
+                        (
+                            quote!(if (#enum_name::#x).as_num() == 0 { #name } else { #name_else }),
+                            quote!(if (#enum_name::#x).as_num() == 0 { #debug } else { #debug_else }),
+                        )
+                    }),
+            };
+
+            let debug_num = match options.debug_num {
+                Some(format) => quote! {
+                    write!(f, #format, self.as_num())?;
+                },
+                None => quote! {},
+            };
+
+            let all_matches = options.display_all.as_ref().map(|all| {
+                if options.case_insensitive {
+                    quote!(token.eq_ignore_ascii_case(#all))
+                } else {
+                    quote!(token == #all)
+                }
+            });
+
+            let (display_all, parse_all) = match &options.display_all {
+                Some(all) => {
+                    let matches = all_matches.as_ref().unwrap();
+                    (
+                        quote! {
+                            if self.is_all() {
+                                return f.write_str(#all);
+                            }
+                        },
+                        quote! {
+                            if #matches {
+                                n |= Self::MASK;
+                                continue;
+                            }
+                        },
+                    )
+                }
+                None => (quote! {}, quote! {}),
+            };
+
+            let alloc_methods = if cfg!(feature = "alloc") {
+                quote! {
+                    /// Collects the named flags contained in `self`, in declaration order.
+                    #[inline]
+                    #vis fn to_vec(&self) -> #krate::__private::Vec<Self> {
+                        self.iter().collect()
+                    }
+
+                    /// Describes which named flags are set and unset, and which stray bits are set.
+                    #vis fn describe(&self) -> #krate::FlagReport<Self, #num> {
+                        #krate::FlagReport {
+                            set: self.iter().collect(),
+                            unset: self.missing().collect(),
+                            stray_bits: self.as_num() & !Self::MASK,
+                        }
+                    }
+
+                    /// Parses every name in `names` and combines the flags, collecting all of the
+                    /// unknown names rather than stopping at the first one.
+                    #vis fn try_from_names<'a, I: core::iter::IntoIterator<Item = &'a str>>(
+                        names: I,
+                    ) -> core::result::Result<Self, #krate::__private::Vec<#krate::__private::String>> {
+                        let mut v = Self::from(0);
+                        let mut errors = #krate::__private::Vec::new();
+                        for name in names {
+                            match Self::from_name(name) {
+                                core::option::Option::Some(flag) => v |= flag,
+                                core::option::Option::None => errors.push(name.into()),
+                            }
+                        }
+                        if errors.is_empty() {
+                            core::result::Result::Ok(v)
+                        } else {
+                            core::result::Result::Err(errors)
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            let op_traits = ["BitOr", "BitAnd", "BitXor"]
+                .iter()
+                .map(|x| Ident::new(x, Span::call_site()))
+                .collect::<Vec<_>>();
+            let op_fns = ["bitor", "bitand", "bitxor"]
+                .iter()
+                .map(|x| Ident::new(x, Span::call_site()))
+                .collect::<Vec<_>>();
+            let op_syms = [quote!(|), quote!(&), quote!(^)];
+            let op_assign_traits = ["BitOrAssign", "BitAndAssign", "BitXorAssign"]
+                .iter()
+                .map(|x| Ident::new(x, Span::call_site()))
+                .collect::<Vec<_>>();
+            let op_assign_fns = ["bitor_assign", "bitand_assign", "bitxor_assign"]
+                .iter()
+                .map(|x| Ident::new(x, Span::call_site()))
+                .collect::<Vec<_>>();
+
+            // the canonical integer `Hash` widens to, through the unsigned type of the
+            // same width to avoid sign extension
+            let num_str = num.to_string();
+            let unsigned = match num_str.strip_prefix('i') {
+                Some(width) => Ident::new(&format!("u{}", width), num.span()),
+                None => num.clone(),
+            };
+            let wide = if num == "u128" || num == "i128" {
+                Ident::new("u128", num.span())
+            } else {
+                Ident::new("u64", num.span())
+            };
+
+            // widening into `u64` and `i64` for logging, skipped for the repr itself,
+            // which already has `From`, and for 128-bit reprs, which don't fit, and into `i64`
+            // from 64-bit unsigned reprs, where the top bit would come out as the sign
+            let widen_to = ["u64", "i64"]
+                .iter()
+                .filter(|wide| num != **wide && num != "u128" && num != "i128")
+                .filter(|wide| !(**wide == "i64" && (unsigned == "u64" || unsigned == "usize")))
+                .map(|wide| Ident::new(wide, num.span()))
+                .collect::<Vec<_>>();
+
+            // an `i64` repr already has `From<i64>`, which the blanket `TryFrom` impl covers
+            // without checking, `try_from_i64` is the checked conversion for every repr
+            let try_from_i64 = if num == "i64" {
+                quote! {}
+            } else {
+                quote! {
+                    impl core::convert::TryFrom<i64> for #enum_name {
+                        type Error = #krate::TryFromIntError;
+
+                        /// Converts from an `i64`, see `try_from_i64`.
+                        #[inline]
+                        fn try_from(value: i64) -> core::result::Result<Self, Self::Error> {
+                            Self::try_from_i64(value)
+                        }
+                    }
+                }
+            };
+
+            let validate = if cfg!(feature = "validate") {
+                quote!(self.debug_assert_valid();)
+            } else {
+                quote! {}
+            };
+
+            // with `parse_ops`, `FromStr` evaluates `|`, `&` and `-` from left to right,
+            // and `!` complements a single name within the named flags
+            let (from_str, parse_ops) = if options.parse_ops {
+                // `ALL` is built in, unless a flag goes by that name
+                let builtin_all = if options.case_insensitive {
+                    quote!(token.eq_ignore_ascii_case("ALL"))
+                } else {
+                    quote!(token == "ALL")
+                };
+                let lookup = quote! {
+                    match Self::from_name(token) {
+                        core::option::Option::Some(flag) => flag.as_num(),
+                        core::option::Option::None if #builtin_all => Self::MASK,
+                        core::option::Option::None => {
+                            return core::result::Result::Err(#krate::ParseFlagsError::new(token));
+                        }
+                    }
+                };
+                let term = match &all_matches {
+                    Some(matches) => quote! {
+                        if #matches { Self::MASK } else { #lookup }
+                    },
+                    None => lookup,
+                };
+                (
+                    quote!(Self::parse_ops(s)),
+                    quote! {
+                        /// Parses names combined with `|`, `&` and `-`, from left to right,
+                        /// where `!` complements a name and `ALL` stands for every flag, e.g. `"ALL & !C"`.
+                        fn parse_ops(s: &str) -> core::result::Result<Self, #krate::ParseFlagsError> {
+                            let mut n: #num = 0;
+                            let mut op = '|';
+                            let mut rest = s;
+                            loop {
+                                let end = rest.find(|c| c == '|' || c == '&' || c == '-').unwrap_or(rest.len());
+                                let token = rest[..end].trim();
+                                let (complement, token) = match token.strip_prefix('!') {
+                                    core::option::Option::Some(token) => (true, token.trim()),
+                                    core::option::Option::None => (false, token),
+                                };
+                                if !token.is_empty() || complement {
+                                    let mut bits = #term;
+                                    if complement {
+                                        bits = !bits & Self::MASK;
+                                    }
+                                    match op {
+                                        '&' => n &= bits,
+                                        '-' => n &= !bits,
+                                        _ => n |= bits,
+                                    }
+                                }
+                                match rest[end..].chars().next() {
+                                    core::option::Option::Some(next) => {
+                                        op = next;
+                                        rest = &rest[end + 1..];
+                                    }
+                                    core::option::Option::None => break,
+                                }
+                            }
+                            core::result::Result::Ok(Self::from_num(n))
+                        }
+                    },
+                )
+            } else {
+                (quote!(Self::parse_with(s, '|')), quote! {})
+            };
+
+            let fmt_traits = ["LowerHex", "UpperHex", "Octal", "Binary"]
+                .iter()
+                .map(|x| Ident::new(x, Span::call_site()))
+                .collect::<Vec<_>>();
+
+            let flags_array = flag_array(
+                quote!((&'static str, #enum_name)),
+                quote!(("", #enum_name::__Composed__(0))),
+                flag_items.iter().zip(&flag_item_strs).map(|(x, name)| quote!((#name, #enum_name::#x))).collect(),
+            );
+            let variants_array = flag_array(
+                quote!(#enum_name),
+                quote!(#enum_name::__Composed__(0)),
+                flag_items.iter().map(|x| quote!(#enum_name::#x)).collect(),
+            );
+            let names_array = flag_array(quote!(&str), quote!(""), flag_item_strs.iter().map(|name| quote!(#name)).collect());
+
+            let impls = quote! {
+                #(#discriminant_checks)*
+
+                impl #enum_name {
+                    /// The bits of every named flag combined.
+                    #vis const MASK: #num = #(#enum_values)|*;
+
+                    /// All of the named flags set.
+                    #vis const ALL: Self = Self::from_num(Self::MASK);
+
+                    /// The number of named flags, excluding the zero variant.
+                    #vis const COUNT: usize = #flag_count;
+
+                    #(#group_consts)*
+
+                    /// The name and value of every named flag, in declaration order,
+                    /// excluding the zero variant.
+                    #vis const FLAGS: [(&'static str, Self); #flag_count] = #flags_array;
+
+                    /// The bit width of the underlying representation.
+                    #vis const BITS: u32 = #num::BITS;
+
+                    #accessors
+
+                    /// Returns `true` if all of the flags in `other` are contained within `self`.
+                    #[inline]
+                    #vis fn has_flag(&self, other: Self) -> bool {
+                        self.contains(other)
+                    }
+
+                    /// Returns `true` if no flags are currently stored.
+                    ///
+                    /// See `is_empty_const` for use in const contexts.
+                    #[inline]
+                    #vis fn is_empty(&self) -> bool {
+                        #num::from(self) == 0
+                    }
+
+                    /// Same as `is_empty`, usable in const contexts,
+                    /// e.g. `const EMPTY: bool = FLAGS.is_empty_const();`.
+                    #[inline]
+                    #vis const fn is_empty_const(self) -> bool {
+                        self.as_num() == 0
+                    }
+
+                    /// Returns `true` if all of the non-zero named flags are set.
+                    ///
+                    /// The zero variant is ignored, and so are bits which don't belong to a named flag.
+                    /// See `is_all_const` for use in const contexts.
+                    #[inline]
+                    #vis fn is_all(&self) -> bool {
+                        self.bits() & Self::MASK == Self::MASK
+                    }
+
+                    /// Same as `is_all`, usable in const contexts,
+                    /// e.g. `const ALL: bool = FLAGS.is_all_const();`.
+                    #[inline]
+                    #vis const fn is_all_const(self) -> bool {
+                        self.as_num() & Self::MASK == Self::MASK
+                    }
+
+                    /// Same as `contains`, usable in const contexts,
+                    /// e.g. `const _: () = assert!(DEFAULTS.contains_const(Flags::A));`.
+                    #[inline]
+                    #vis const fn contains_const(self, other: Self) -> bool {
+                        let b = other.as_num();
+                        self.as_num() & b == b
+                    }
+
+                    /// Returns `true` if any bit which doesn't belong to a named flag is set.
+                    #[inline]
+                    #vis const fn has_stray_bits(&self) -> bool {
+                        self.as_num() & !Self::MASK != 0
+                    }
+
+                    /// Returns `true` if every set bit is within the lowest `bits` positions,
+                    /// e.g. whether the value can be sent over a narrower field.
+                    #[inline]
+                    #vis const fn fits_in_bits(&self, bits: u32) -> bool {
+                        match (self.as_num() as #unsigned).checked_shr(bits) {
+                            core::option::Option::Some(rest) => rest == 0,
+                            core::option::Option::None => true,
+                        }
+                    }
+
+                    /// Panics in debug builds if `self` has stray bits, see `has_stray_bits`.
+                    ///
+                    /// With the `validate` feature the mutating methods and operators call it.
+                    #[inline]
+                    #vis fn debug_assert_valid(&self) {
+                        debug_assert!(
+                            !self.has_stray_bits(),
+                            "{} has stray bits {:#x}",
+                            #enum_name_str,
+                            self.as_num() & !Self::MASK
+                        );
+                    }
+
+                    /// Returns `true` if exactly one named flag is set, ignoring any bits
+                    /// that don't belong to a named flag.
+                    ///
+                    /// Unlike `is_exactly_one_bit`, stray bits don't count.
+                    #vis fn is_single(&self) -> bool {
+                        let n = self.as_num() & Self::MASK;
+                        Self::variants().iter().any(|flag| flag.as_num() == n)
+                    }
+
+                    /// Returns the named flag `self` holds, or an error with the number of named flags
+                    /// it holds if that's not exactly one. Bits which don't belong to a named flag are dropped.
+                    #vis fn try_single(self) -> core::result::Result<Self, #krate::MultiFlagError> {
+                        if self.is_single() {
+                            core::result::Result::Ok(Self::from_num(self.as_num() & Self::MASK))
+                        } else {
+                            core::result::Result::Err(#krate::MultiFlagError::new(self.iter().count()))
+                        }
+                    }
+
+                    /// Returns how many bits of the named flags are set, ignoring bits which don't
+                    /// belong to a named flag, unlike `bits().count_ones()`.
+                    #[inline]
+                    #vis fn set_count(&self) -> u32 {
+                        (self.as_num() & Self::MASK).count_ones()
+                    }
+
+                    /// Returns how many bits of the named flags aren't set, i.e. `COUNT - set_count()`
+                    /// when every flag is a single bit.
+                    #[inline]
+                    #vis fn unset_count(&self) -> u32 {
+                        Self::MASK.count_ones() - self.set_count()
+                    }
+
+                    /// Returns `true` if exactly one bit of the raw value is set.
+                    ///
+                    /// Unlike `is_single`, stray bits count, so this is the check to use
+                    /// when auditing external input.
+                    #[inline]
+                    #vis fn is_exactly_one_bit(&self) -> bool {
+                        self.as_num().count_ones() == 1
+                    }
+
+                    /// Returns `true` if all of the flags in `other` are contained within `self`.
+                    #[inline]
+                    #vis fn contains(&self, other: Self) -> bool {
+                        let b = other.as_num();
+                        self.as_num() & b == b
+                    }
+
+                    /// Returns `true` if any of the values in `flags` is contained within `self`.
+                    #vis fn any_of(&self, flags: &[Self]) -> bool {
+                        flags.iter().any(|flag| self.contains(Self::from_num(flag.as_num())))
+                    }
+
+                    /// Returns `true` if every value in `flags` is contained within `self`.
+                    #vis fn all_of(&self, flags: &[Self]) -> bool {
+                        flags.iter().all(|flag| self.contains(Self::from_num(flag.as_num())))
+                    }
+
+                    /// Returns `true` if not all of the flags in `other` are contained within `self`,
+                    /// i.e. `!self.contains(other)`.
+                    #[inline]
+                    #vis fn is_clear(&self, other: Self) -> bool {
+                        !self.contains(other)
+                    }
+
+                    #[inline]
+                    #vis fn clear(&mut self) {
+                        *self = Self::from(0);
+                    }
+
+                    /// Sets every named flag in-place, without any other bits.
+                    #[inline]
+                    #vis fn set_all(&mut self) {
+                        *self = Self::ALL;
+                    }
+
+                    /// Unsets every flag in-place, same as `clear`.
+                    #[inline]
+                    #vis fn unset_all(&mut self) {
+                        self.clear();
+                    }
+
+                    /// Inserts the specified flags in-place.
+                    #[inline]
+                    #vis fn insert(&mut self, other: Self) {
+                        *self |= other;
+                    }
+
+                    /// Inserts the flag declared as `name`, surrounding whitespace ignored,
+                    /// or fails if there's no such flag.
+                    #vis fn insert_name(&mut self, name: &str) -> core::result::Result<(), #krate::ParseFlagsError> {
+                        let name = name.trim();
+                        match Self::from_name(name) {
+                            core::option::Option::Some(flag) => {
+                                *self = Self::from_num(self.as_num() | flag.as_num());
+                                core::result::Result::Ok(())
+                            }
+                            core::option::Option::None => core::result::Result::Err(#krate::ParseFlagsError::new(name)),
+                        }
+                    }
+
+                    /// Removes the specified flags in-place.
+                    #[inline]
+                    #vis fn remove(&mut self, other: Self) {
+                        *self &= !other;
+                    }
+
+                    /// Inserts or removes the specified flags depending on the passed value.
+                    #[inline]
+                    #vis fn set(&mut self, other: Self, value: bool) {
+                        if value {
+                            self.insert(other);
+                        } else {
+                            self.remove(other);
+                        }
+                    }
+
+                    /// Removes the `old` flags, then inserts the `new` ones, so flags in both end up set.
+                    #[inline]
+                    #vis fn replace(&mut self, old: Self, new: Self) {
+                        self.remove(old);
+                        self.insert(new);
+                    }
+
+                    /// Toggles the specified flags in-place: set flags become unset and unset flags become set.
+                    ///
+                    /// When `other` holds several flags, each of them is toggled independently,
+                    /// e.g. toggling `A | B` on `A` gives `B`.
+                    #[inline]
+                    #vis fn toggle(&mut self, other: Self) {
+                        *self ^= other;
+                    }
+
+                    /// Toggles the specified flags in-place, same as `toggle`.
+                    #[inline]
+                    #vis fn flip(&mut self, other: Self) {
+                        self.toggle(other);
+                    }
+
+                    /// Returns an entry to query and change `flag` in-place, e.g.
+                    /// `flags.modify(Flags::A).set()`.
+                    #[inline]
+                    #vis fn modify(&mut self, flag: Self) -> #krate::FlagEntry<'_, Self> {
+                        #krate::FlagEntry::new(self, flag)
+                    }
+
+                    /// Toggles every named flag in-place, see `invert_known`.
+                    #[inline]
+                    #vis fn toggle_all(&mut self) {
+                        *self = self.invert_known();
+                    }
+
+                    /// Returns the named flags which aren't set in `self`, i.e. `ALL ^ self`.
+                    ///
+                    /// Unlike `!self`, this doesn't flip the bits which don't belong to a named flag,
+                    /// they're dropped instead, so inverting twice gives back the named flags.
+                    #[inline]
+                    #vis fn invert_known(&self) -> Self {
+                        Self::from_num(!self.as_num() & Self::MASK)
+                    }
+
+                    /// Returns the intersection between the flags in `self` and
+                    #[inline]
+                    #vis fn intersection(&self, other: Self) -> Self {
+                        Self::from_num(self.as_num() & other.as_num())
+                    }
+
+                    /// Returns the union of between the flags in `self` and `other`.
+                    #[inline]
+                    #vis fn union(&self, other: Self) -> Self {
+                        Self::from_num(self.as_num() | other.as_num())
+                    }
+
+                    /// Returns the difference between the flags in `self` and `other`.
+                    #[inline]
+                    #vis fn difference(&self, other: Self) -> Self {
+                        Self::from_num(self.as_num() & !other.as_num())
+                    }
+
+                    /// Returns the [symmetric difference][sym-diff] between the flags
+                    /// in `self` and `other`.
+                    ///
+                    /// This is the raw `^`, so bits which don't belong to a named flag can show up,
+                    /// see `symmetric_difference_known`.
+                    #[inline]
+                    #vis fn symmetric_difference(&self, other: Self) -> Self {
+                        Self::from_num(self.as_num() ^ other.as_num())
+                    }
+
+                    /// Returns the named flags set in either `self` or `other` but not both,
+                    /// i.e. `(self ^ other) & MASK`, with the bits which don't belong to a named flag dropped.
+                    #[inline]
+                    #vis fn symmetric_difference_known(&self, other: Self) -> Self {
+                        Self::from_num((self.as_num() ^ other.as_num()) & Self::MASK)
+                    }
+
+                    /// Splits `self` into the flags within `mask` and the rest, i.e.
+                    /// `(self & mask, self & !mask)`.
+                    #[inline]
+                    #vis fn split(&self, mask: Self) -> (Self, Self) {
+                        let m = mask.as_num();
+                        (Self::from_num(self.as_num() & m), Self::from_num(self.as_num() & !m))
+                    }
+
+                    /// Returns `self` if `cond` is `true`, otherwise the empty value,
+                    /// e.g. `Flags::A.when(a) | Flags::B.when(b)`.
+                    #[inline]
+                    #vis const fn when(self, cond: bool) -> Self {
+                        if cond {
+                            self
+                        } else {
+                            Self::from_num(0)
+                        }
+                    }
+
+                    /// Sets the flags in `delta.to_set`, then clears the flags in `delta.to_clear`.
+                    #vis fn apply(&mut self, delta: #krate::FlagDelta<Self>) {
+                        *self = Self::from_num((self.as_num() | delta.to_set.as_num()) & !delta.to_clear.as_num());
+                        #validate
+                    }
+
+                    /// Returns the delta which undoes applying `delta` to the current value,
+                    /// i.e. clears what it newly sets and sets again what it clears.
+                    #vis fn invert_delta(&self, delta: &#krate::FlagDelta<Self>) -> #krate::FlagDelta<Self> {
+                        #krate::FlagDelta::new(
+                            Self::from_num(self.as_num() & delta.to_clear.as_num()),
+                            Self::from_num(delta.to_set.as_num() & !self.as_num()),
+                        )
+                    }
+
+                    /// Combines the flags in `self` and `other` with the operation chosen by `op`.
+                    #vis fn merge_with(&self, other: Self, op: #krate::MergeOp) -> Self {
+                        match op {
+                            #krate::MergeOp::Union => self.union(other),
+                            #krate::MergeOp::Intersection => self.intersection(other),
+                            #krate::MergeOp::Difference => self.difference(other),
+                            #krate::MergeOp::Xor => self.symmetric_difference(other),
+                        }
+                    }
+
+                    /// Returns the union of all of the values in `items`, the empty value if there are none.
+                    #vis const fn union_all(items: &[Self]) -> Self {
+                        let mut n: #num = 0;
+                        let mut i = 0;
+                        while i < items.len() {
+                            n |= items[i].as_num();
+                            i += 1;
+                        }
+                        Self::from_num(n)
+                    }
+
+                    /// Returns the intersection of all of the values in `items`.
+                    ///
+                    /// An empty slice returns `ALL`, the identity of the intersection.
+                    #vis const fn intersection_all(items: &[Self]) -> Self {
+                        let mut n: #num = Self::MASK;
+                        let mut i = 0;
+                        while i < items.len() {
+                            n &= items[i].as_num();
+                            i += 1;
+                        }
+                        Self::from_num(n)
+                    }
+
+                    /// Returns the union of the optional flags, or `None` if neither is present.
+                    #vis fn or_opt(
+                        a: core::option::Option<Self>,
+                        b: core::option::Option<Self>,
+                    ) -> core::option::Option<Self> {
+                        match (a, b) {
+                            (core::option::Option::Some(a), core::option::Option::Some(b)) => {
+                                core::option::Option::Some(a | b)
+                            }
+                            (a, core::option::Option::None) => a,
+                            (core::option::Option::None, b) => b,
+                        }
+                    }
+
+                    /// Moves every set flag `n` positions forward in `variants()`, wrapping around.
+                    ///
+                    /// This operates on the indices of the named flags, not on the raw bits,
+                    /// so bits which don't belong to a named flag are dropped.
+                    #vis fn rotate_left(&self, n: u32) -> Self {
+                        let flags = Self::variants();
+                        let mut bits: #num = 0;
+                        for (i, flag) in flags.iter().enumerate() {
+                            if self.contains(Self::from_num(flag.as_num())) {
+                                // reduced first, `i + n` can overflow a 32-bit `usize`
+                                bits |= flags[(i + n as usize % flags.len()) % flags.len()].as_num();
+                            }
+                        }
+                        Self::from_num(bits)
+                    }
+
+                    /// Removes any bits that don't belong to a named flag, in-place.
+                    #[inline]
+                    #vis fn normalize(&mut self) {
+                        *self = Self::from_num(self.as_num() & Self::MASK);
+                    }
+
+                    /// Returns a copy of `self` with any bits that don't belong to a named flag removed.
+                    #[inline]
+                    #vis fn normalized(self) -> Self {
+                        Self::from_num(self.as_num() & Self::MASK)
+                    }
+
+                    /// Returns the named flags, in declaration order, excluding the zero variant.
+                    #[inline]
+                    #vis fn variants() -> &'static [Self] {
+                        const VARIANTS: [#enum_name; #flag_count] = #variants_array;
+                        &VARIANTS
+                    }
+
+                    /// Returns the position of `flag` in `variants()`, or `None` if it isn't
+                    /// exactly one named flag.
+                    #vis fn index_of(flag: Self) -> core::option::Option<usize> {
+                        Self::variants().iter().position(|x| x.as_num() == flag.as_num())
+                    }
+
+                    /// Returns the named flag at `index` in `variants()`, if any.
+                    #[inline]
+                    #vis fn flag_at(index: usize) -> core::option::Option<Self> {
+                        Self::variants().get(index).map(|flag| Self::from_num(flag.as_num()))
+                    }
+
+                    /// Returns the named flag declared after `self`, wrapping around to the first one.
+                    ///
+                    /// If `self` isn't exactly one named flag, e.g. empty or composed, returns the first flag.
+                    #vis fn next(self) -> Self {
+                        let i = match Self::index_of(self) {
+                            core::option::Option::Some(i) if i + 1 < Self::COUNT => i + 1,
+                            _ => 0,
+                        };
+                        Self::flag_at(i).unwrap_or_else(|| Self::from_num(0))
+                    }
+
+                    /// Returns the named flag declared before `self`, wrapping around to the last one.
+                    ///
+                    /// If `self` isn't exactly one named flag, e.g. empty or composed, returns the last flag.
+                    #vis fn prev(self) -> Self {
+                        let i = match Self::index_of(self) {
+                            core::option::Option::Some(i) if i > 0 => i - 1,
+                            _ => Self::COUNT.wrapping_sub(1),
+                        };
+                        Self::flag_at(i).unwrap_or_else(|| Self::from_num(0))
+                    }
+
+                    /// Returns the names of the named flags, in the same order as `variants()`.
+                    #[inline]
+                    #vis fn names() -> &'static [&'static str] {
+                        const NAMES: [&str; #flag_count] = #names_array;
+                        &NAMES
+                    }
+
+                    /// Returns the name of the variant if `self` is exactly a named variant.
+                    #vis fn name(&self) -> core::option::Option<&'static str> {
+                        match self {
+                            #(
+                                #enum_name::#enum_items => core::option::Option::Some(#enum_item_strs),
+                            )*
+                            #enum_name::__Composed__(_) => core::option::Option::None
+                        }
+                    }
+
+                    /// Returns an iterator over the named flags contained in `self`, in declaration order.
+                    #[inline]
+                    #vis fn iter(&self) -> impl core::iter::Iterator<Item = Self> + '_ {
+                        self.iter_named().map(|(flag, _)| flag)
+                    }
+
+                    /// Calls `f` with each named flag contained in `self`, in declaration order.
+                    ///
+                    /// Same as `iter().for_each(f)`, without allocating or importing `Iterator`,
+                    /// which makes it handy in `no_std` code.
+                    #vis fn for_each_flag<F: core::ops::FnMut(Self)>(&self, mut f: F) {
+                        for flag in self.iter() {
+                            f(flag);
+                        }
+                    }
+
+                    /// Returns an iterator over the named flags not contained in `self`, in declaration order.
+                    #vis fn missing(&self) -> impl core::iter::Iterator<Item = Self> + '_ {
+                        Self::variants()
+                            .iter()
+                            .map(|flag| Self::from_num(flag.as_num()))
+                            .filter(move |flag| !self.contains(Self::from_num(flag.as_num())))
+                    }
+
+                    /// Writes the named flags contained in `self` into `buf`, in declaration order,
+                    /// and returns how many were written.
+                    ///
+                    /// Flags which don't fit in `buf` are left out, no allocation is needed.
+                    #vis fn collect_into(&self, buf: &mut [Self]) -> usize {
+                        let mut len = 0;
+                        for (slot, flag) in buf.iter_mut().zip(self.iter()) {
+                            *slot = flag;
+                            len += 1;
+                        }
+                        len
+                    }
+
+                    /// Returns an iterator over the named flags contained in `self` along with their names,
+                    /// in declaration order.
+                    #vis fn iter_named(&self) -> impl core::iter::Iterator<Item = (Self, &'static str)> + '_ {
+                        Self::variants()
+                            .iter()
+                            .zip(Self::names().iter())
+                            .map(|(flag, name)| (Self::from_num(flag.as_num()), *name))
+                            .filter(move |(flag, _)| self.contains(Self::from_num(flag.as_num())))
+                    }
+
+                    /// Returns an iterator over the named flags contained in `self`, in declaration order,
+                    /// followed by the index of every stray bit, to audit corrupted or newer data.
+                    #vis fn iter_all_bits(&self) -> impl core::iter::Iterator<Item = #krate::BitEntry<Self>> + '_ {
+                        let stray = (self.as_num() & !Self::MASK) as #unsigned;
+                        self.iter()
+                            .map(#krate::BitEntry::Named)
+                            .chain(
+                                (0..(core::mem::size_of::<#num>() * 8) as u32)
+                                    .filter(move |i| stray >> i & 1 != 0)
+                                    .map(#krate::BitEntry::Unknown),
+                            )
+                    }
+
+                    /// Returns whether each named flag is set, in the same order as `variants()`.
+                    #[inline]
+                    #vis fn to_bools(&self) -> [bool; #enum_name::COUNT] {
+                        let mut bools = [false; #enum_name::COUNT];
+                        for (set, flag) in bools.iter_mut().zip(Self::variants().iter()) {
+                            *set = self.contains(Self::from_num(flag.as_num()));
+                        }
+                        bools
+                    }
+
+                    /// Builds a value from one `bool` per named flag, in the same order as `variants()`.
+                    #vis fn from_bools(bools: [bool; #enum_name::COUNT]) -> Self {
+                        let mut n: #num = 0;
+                        for (flag, set) in Self::variants().iter().zip(bools.iter()) {
+                            if *set {
+                                n |= flag.as_num();
+                            }
+                        }
+                        Self::from_num(n)
+                    }
+
+                    /// Builds a value from the named flags `f` returns `true` for, called once per flag
+                    /// in the same order as `variants()`.
+                    #vis fn from_fn<F: core::ops::FnMut(Self) -> bool>(mut f: F) -> Self {
+                        let mut n: #num = 0;
+                        for flag in Self::variants() {
+                            if f(Self::from_num(flag.as_num())) {
+                                n |= flag.as_num();
+                            }
+                        }
+                        Self::from_num(n)
+                    }
+
+                    #from_name
+
+                    /// Parses every name in `names` and combines the flags, stopping at the first
+                    /// unknown name. Unlike `try_from_names`, it doesn't need `alloc`.
+                    #vis fn from_names<'a, I: core::iter::IntoIterator<Item = &'a str>>(
+                        names: I,
+                    ) -> core::result::Result<Self, #krate::ParseFlagsError> {
+                        let mut n: #num = 0;
+                        for name in names {
+                            match Self::from_name(name) {
+                                core::option::Option::Some(flag) => n |= flag.as_num(),
+                                core::option::Option::None => {
+                                    return core::result::Result::Err(#krate::ParseFlagsError::new(name));
+                                }
+                            }
+                        }
+                        core::result::Result::Ok(Self::from_num(n))
+                    }
+
+                    #alloc_methods
+
+                    /// Parses comma separated flag names, e.g. `"A, C"`.
+                    #[inline]
+                    #vis fn parse_csv(s: &str) -> core::result::Result<Self, #krate::ParseFlagsError> {
+                        Self::parse_with(s, ',')
+                    }
+
+                    /// Parses flag names separated by `sep`, e.g. `Flags::parse_with("A + C", '+')`.
+                    ///
+                    /// Surrounding whitespace and empty tokens, e.g. in `"A++C"`, are ignored,
+                    /// so whitespace separated names can be parsed with `' '`. Repeated names are fine.
+                    #vis fn parse_with(s: &str, sep: char) -> core::result::Result<Self, #krate::ParseFlagsError> {
+                        let mut n: #num = 0;
+                        for token in s.split(sep) {
+                            let token = token.trim();
+                            if token.is_empty() {
+                                continue;
+                            }
+                            #parse_all
+                            match Self::from_name(token) {
+                                core::option::Option::Some(flag) => n |= flag.as_num(),
+                                core::option::Option::None => {
+                                    return core::result::Result::Err(#krate::ParseFlagsError::new(token));
+                                }
+                            }
+                        }
+                        core::result::Result::Ok(Self::from_num(n))
+                    }
+
+                    #parse_ops
+
+                    /// Parses the raw value written in decimal, or in hex or binary with a `0x` or `0b` prefix.
+                    fn from_num_str(s: &str) -> core::option::Option<Self> {
+                        let s = s.trim();
+                        let n = if let core::option::Option::Some(hex) = s.strip_prefix("0x") {
+                            #num::from_str_radix(hex, 16)
+                        } else if let core::option::Option::Some(bin) = s.strip_prefix("0b") {
+                            #num::from_str_radix(bin, 2)
+                        } else {
+                            s.parse::<#num>()
+                        };
+                        n.ok().map(Self::from_num)
+                    }
+
+                    #[inline]
+                    #vis const fn from_num(n: #num) -> Self {
+                        use #enum_name::*;
+                        match n {
+                            #(
+                                #from_num_arms => #enum_items,
+                            )*
+                            _ => __Composed__(n)
+                        }
+                    }
+
+                    #[inline]
+                    #vis const fn as_num(&self) -> #num {
+                        match self {
+                            #(
+                                #enum_name::#enum_items => #enum_values,
+                            )*
+                            #enum_name::__Composed__(n) => *n,
+                        }
+                    }
+
+                    /// Returns the raw value, same as `as_num`.
+                    #[inline]
+                    #vis const fn bits(&self) -> #num {
+                        self.as_num()
+                    }
+
+                    /// Returns the raw value as little-endian bytes.
+                    #[inline]
+                    #vis const fn to_le_bytes(&self) -> [u8; core::mem::size_of::<#num>()] {
+                        self.as_num().to_le_bytes()
+                    }
+
+                    /// Returns the raw value as big-endian bytes.
+                    #[inline]
+                    #vis const fn to_be_bytes(&self) -> [u8; core::mem::size_of::<#num>()] {
+                        self.as_num().to_be_bytes()
+                    }
+
+                    /// Converts from the raw value in little-endian bytes, keeping any stray bits.
+                    #[inline]
+                    #vis const fn from_le_bytes(bytes: [u8; core::mem::size_of::<#num>()]) -> Self {
+                        Self::from_num(#num::from_le_bytes(bytes))
+                    }
+
+                    /// Converts from the raw value in big-endian bytes, keeping any stray bits.
+                    #[inline]
+                    #vis const fn from_be_bytes(bytes: [u8; core::mem::size_of::<#num>()]) -> Self {
+                        Self::from_num(#num::from_be_bytes(bytes))
+                    }
+
+                    /// Converts into the raw value, e.g. to pass it across an FFI boundary.
+                    #[inline]
+                    #vis const fn into_raw(self) -> #num {
+                        self.as_num()
+                    }
+
+                    /// Converts from a raw value, e.g. one received across an FFI boundary.
+                    ///
+                    /// # Safety
+                    ///
+                    /// No check is done, the caller should make sure `n` has no bits which don't belong
+                    /// to a named flag. The conversion itself can't cause undefined behavior, `unsafe`
+                    /// marks the boundary. Prefer `from_bits` or `from_bits_truncate` on the Rust side.
+                    #[inline]
+                    #vis const unsafe fn from_raw(n: #num) -> Self {
+                        Self::from_num(n)
+                    }
+
+                    /// Converts from a `u64`, failing if any bit beyond the width of the repr is set.
+                    ///
+                    /// A signed repr takes the bits as they are, e.g. `0x80` sets the sign bit of an `i8`.
+                    #vis fn from_wide(n: u64) -> core::result::Result<Self, #krate::TruncationError> {
+                        match <#unsigned as core::convert::TryFrom<u64>>::try_from(n) {
+                            core::result::Result::Ok(n) => core::result::Result::Ok(Self::from_num(n as #num)),
+                            core::result::Result::Err(_) => {
+                                core::result::Result::Err(#krate::TruncationError::new(n, Self::BITS))
+                            }
+                        }
+                    }
+
+                    /// Converts from an `i64`, failing if it doesn't fit in the repr
+                    /// or has any bit set which doesn't belong to a named flag.
+                    ///
+                    /// `TryFrom<i64>` does the same, except for an `i64` repr, where it's the
+                    /// blanket impl over `From<i64>`, which can't fail and keeps stray bits.
+                    #vis fn try_from_i64(value: i64) -> core::result::Result<Self, #krate::TryFromIntError> {
+                        match <#num as core::convert::TryFrom<i64>>::try_from(value) {
+                            core::result::Result::Ok(n) if n & !Self::MASK == 0 => {
+                                core::result::Result::Ok(Self::from_num(n))
+                            }
+                            core::result::Result::Ok(n) => core::result::Result::Err(
+                                #krate::TryFromIntError::stray_bits(value, (n & !Self::MASK) as u64),
+                            ),
+                            core::result::Result::Err(_) => core::result::Result::Err(
+                                #krate::TryFromIntError::out_of_range(value, Self::BITS),
+                            ),
+                        }
+                    }
+
+                    /// Converts from the raw value, returning `None` if any bit doesn't belong
+                    /// to a named flag.
+                    #[inline]
+                    #vis const fn from_bits(bits: #num) -> core::option::Option<Self> {
+                        if bits & !Self::MASK == 0 {
+                            core::option::Option::Some(Self::from_num(bits))
+                        } else {
+                            core::option::Option::None
+                        }
+                    }
+
+                    /// Converts from the raw value, dropping any bit that doesn't belong to a named flag.
+                    #[inline]
+                    #vis const fn from_bits_truncate(bits: #num) -> Self {
+                        Self::from_num(bits & Self::MASK)
+                    }
+
+                    /// Converts from the raw value, keeping every bit as is, same as `from_num`.
+                    ///
+                    /// Bits which don't belong to a named flag are retained, so they show up in
+                    /// `as_num`, comparisons and the bitwise operators, but never in `iter` or `Debug`.
+                    /// Use `normalize` to drop them later.
+                    #[inline]
+                    #vis const fn from_bits_retain(bits: #num) -> Self {
+                        Self::from_num(bits)
+                    }
+
+                    /// Combines raw values, e.g. discriminants loaded at runtime, into one value.
+                    ///
+                    /// Unknown bits are kept, call `normalized` on the result to drop them.
+                    #vis const fn from_discriminants(values: &[#num]) -> Self {
+                        let mut n: #num = 0;
+                        let mut i = 0;
+                        while i < values.len() {
+                            n |= values[i];
+                            i += 1;
+                        }
+                        Self::from_num(n)
+                    }
+                }
+
+                impl From<#num> for #enum_name {
+                    #[inline]
+                    fn from(n: #num) -> Self {
+                        Self::from_num(n)
+                    }
+                }
+
+                impl From<#enum_name> for #num {
+                    #[inline]
+                    fn from(s: #enum_name) -> Self {
+                        s.as_num()
+                    }
+                }
+
+                impl From<&#enum_name> for #num {
+                    #[inline]
+                    fn from(s: &#enum_name) -> Self {
+                        s.as_num()
+                    }
+                }
+
+                #(
+                    impl From<#enum_name> for #widen_to {
+                        /// Widens the bits, zero-extended, so a signed repr's sign bit stays a single bit.
+                        #[inline]
+                        fn from(s: #enum_name) -> Self {
+                            s.as_num() as #unsigned as #widen_to
+                        }
+                    }
+                )*
+
+                #try_from_i64
+
+                impl core::convert::TryFrom<#enum_name> for &'static str {
+                    type Error = #krate::NotSingleFlagError;
+
+                    /// Returns the name of the flag if the value is exactly one named flag.
+                    fn try_from(value: #enum_name) -> core::result::Result<Self, Self::Error> {
+                        #enum_name::index_of(value)
+                            .map(|i| #enum_name::names()[i])
+                            .ok_or(#krate::NotSingleFlagError)
+                    }
+                }
+
+                impl core::ops::BitOr for #enum_name {
+                    type Output = Self;
+                    #[inline]
+                    fn bitor(self, rhs: Self) -> Self::Output {
+                        let a: #num = self.into();
+                        let b: #num = rhs.into();
+                        let c = a | b;
+                        Self::from(c)
+                    }
+                }
+
+                impl core::ops::BitAnd for #enum_name {
+                    type Output = Self;
+                    #[inline]
+                    fn bitand(self, rhs: Self) -> Self::Output {
+                        let a: #num = self.into();
+                        let b: #num = rhs.into();
+                        let c = a & b;
+                        Self::from(c)
+                    }
+                }
+
+                impl core::ops::BitXor for #enum_name {
+                    type Output = Self;
+                    #[inline]
+                    fn bitxor(self, rhs: Self) -> Self::Output {
+                        let a: #num = self.into();
+                        let b: #num = rhs.into();
+                        let c = a ^ b;
+                        Self::from(c)
+                    }
+                }
+
+                impl core::ops::Not for #enum_name {
+                    type Output = Self;
+
+                    #[inline]
+                    fn not(self) -> Self::Output {
+                        let a: #num = self.into();
+                        Self::from(!a)
+                    }
+                }
+
+                /// `a + b` is the union of the flags, same as `a | b`, not an arithmetic sum.
+                impl core::ops::Add for #enum_name {
+                    type Output = Self;
+
+                    #[inline]
+                    fn add(self, rhs: Self) -> Self::Output {
+                        self.union(rhs)
+                    }
+                }
+
+                impl core::ops::Sub for #enum_name {
+                    type Output = Self;
+
+                    #[inline]
+                    fn sub(self, rhs: Self) -> Self::Output {
+                        self & (!rhs)
+                    }
+                }
+
+                #(
+                    impl core::ops::#op_traits for &#enum_name {
+                        type Output = #enum_name;
+                        #[inline]
+                        fn #op_fns(self, rhs: Self) -> Self::Output {
+                            #enum_name::from_num(self.as_num() #op_syms rhs.as_num())
+                        }
+                    }
+                )*
+
+                #(
+                    impl core::ops::#op_traits<#num> for #enum_name {
+                        type Output = Self;
+                        #[inline]
+                        fn #op_fns(self, rhs: #num) -> Self::Output {
+                            Self::from_num(self.as_num() #op_syms rhs)
+                        }
+                    }
+
+                    impl core::ops::#op_traits<#enum_name> for #num {
+                        type Output = #enum_name;
+                        #[inline]
+                        fn #op_fns(self, rhs: #enum_name) -> Self::Output {
+                            #enum_name::from_num(self #op_syms rhs.as_num())
+                        }
+                    }
+                )*
+
+                #(
+                    impl core::ops::#op_assign_traits<#num> for #enum_name {
+                        /// Applies the raw bits as they are, so bits which don't belong to
+                        /// a named flag can end up set, like with `from_bits_retain`.
+                        #[inline]
+                        fn #op_assign_fns(&mut self, rhs: #num) {
+                            *self = Self::from_num(self.as_num() #op_syms rhs);
+                        }
+                    }
+                )*
+
+                impl core::ops::Not for &#enum_name {
+                    type Output = #enum_name;
+
+                    #[inline]
+                    fn not(self) -> Self::Output {
+                        #enum_name::from_num(!self.as_num())
+                    }
+                }
+
+                impl core::ops::Add for &#enum_name {
+                    type Output = #enum_name;
+
+                    #[inline]
+                    fn add(self, rhs: Self) -> Self::Output {
+                        self.union(#enum_name::from_num(rhs.as_num()))
+                    }
+                }
+
+                impl core::ops::Sub for &#enum_name {
+                    type Output = #enum_name;
+
+                    #[inline]
+                    fn sub(self, rhs: Self) -> Self::Output {
+                        #enum_name::from_num(self.as_num() & !rhs.as_num())
+                    }
+                }
+
+                impl core::ops::BitOrAssign for #enum_name {
+                    #[inline]
+                    fn bitor_assign(&mut self, rhs: Self) {
+                        *self = Self::from_num(self.as_num() | rhs.as_num());
+                        #validate
+                    }
+                }
+
+                impl core::ops::BitAndAssign for #enum_name {
+                    #[inline]
+                    fn bitand_assign(&mut self, rhs: Self) {
+                        *self = Self::from_num(self.as_num() & rhs.as_num());
+                        #validate
+                    }
+                }
+
+                impl core::ops::BitXorAssign for #enum_name {
+                    #[inline]
+                    fn bitxor_assign(&mut self, rhs: Self) {
+                        *self = Self::from_num(self.as_num() ^ rhs.as_num());
+                        #validate
+                    }
+                }
+
+                impl core::ops::AddAssign for #enum_name {
+                    #[inline]
+                    fn add_assign(&mut self, rhs: Self) {
+                        *self = self.union(rhs);
+                        #validate
+                    }
+                }
+
+                impl core::ops::SubAssign for #enum_name {
+                    #[inline]
+                    fn sub_assign(&mut self, rhs: Self) {
+                        *self = Self::from_num(self.as_num() & !rhs.as_num());
+                        #validate
+                    }
+                }
+
+                impl core::fmt::Display for #enum_name {
+                    /// Lists the contained flag names separated by `|`, e.g. `A | C`,
+                    /// which `FromStr` parses back.
+                    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        #display_all
+                        let mut first = true;
+                        for (_, name) in self.iter_named() {
+                            if !first {
+                                f.write_str(" | ")?;
+                            }
+                            first = false;
+                            f.write_str(name)?;
+                        }
+                        // a value with only stray bits isn't the zero variant
+                        if first && self.is_empty() {
+                            f.write_str(#empty_name)?;
+                        }
+                        core::result::Result::Ok(())
+                    }
+                }
+
+                impl core::fmt::Debug for #enum_name {
+                    /// Lists the contained flags, e.g. `(Flags::A | Flags::C)`, in alternate mode too.
+                    ///
+                    /// A labeled `{:#x?}` isn't possible on stable: the debug-hex flags of
+                    /// `Formatter` are unstable, so it can't be told apart from `{:#?}`, which has to
+                    /// keep the names. `{:#x}` labels the raw value, e.g. `Flags(0x5)`.
+                    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        const FIRSTS: [&str; #flag_count] = #debug_firsts;
+                        const RESTS: [&str; #flag_count] = #debug_rests;
+                        let mut first = true;
+                        for (i, flag) in #enum_name::variants().iter().enumerate() {
+                            if self.contains(#enum_name::from_num(flag.as_num())) {
+                                f.write_str(if first { FIRSTS[i] } else { RESTS[i] })?;
+                                first = false;
+                            }
+                        }
+                        if first {
+                            f.write_str(if self.is_empty() { #debug_empty } else { "(" })?;
+                        }
+                        #debug_num
+                        f.write_str(")")
+                    }
+                }
+
+                #(
+                    impl core::fmt::#fmt_traits for #enum_name {
+                        /// Formats the raw value, labeled with the type name in alternate mode,
+                        /// e.g. `{:#x}` prints `Flags(0x5)` while `{:x}` prints `5`.
+                        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                            let n = self.as_num();
+                            if f.alternate() {
+                                write!(f, "{}(", #enum_name_str)?;
+                                core::fmt::#fmt_traits::fmt(&n, f)?;
+                                write!(f, ")")
+                            } else {
+                                core::fmt::#fmt_traits::fmt(&n, f)
+                            }
+                        }
+                    }
+                )*
+
+                /// Sums up to the union of the flags, `+` isn't defined for flags otherwise.
+                impl core::iter::Sum for #enum_name {
+                    fn sum<I: core::iter::Iterator<Item = Self>>(iter: I) -> Self {
+                        Self::from_num(iter.fold(0, |n, flag| n | flag.as_num()))
+                    }
+                }
+
+                /// Sums up to the union of the flags, `+` isn't defined for flags otherwise.
+                impl<'a> core::iter::Sum<&'a #enum_name> for #enum_name {
+                    fn sum<I: core::iter::Iterator<Item = &'a Self>>(iter: I) -> Self {
+                        Self::from_num(iter.fold(0, |n, flag| n | flag.as_num()))
+                    }
+                }
+
+                /// Multiplies out to the intersection of the flags, starting from `ALL`,
+                /// so an empty iterator gives `ALL`, like `intersection_all`.
+                impl core::iter::Product for #enum_name {
+                    fn product<I: core::iter::Iterator<Item = Self>>(iter: I) -> Self {
+                        Self::from_num(iter.fold(Self::MASK, |n, flag| n & flag.as_num()))
+                    }
+                }
+
+                /// Multiplies out to the intersection of the flags, starting from `ALL`,
+                /// so an empty iterator gives `ALL`, like `intersection_all`.
+                impl<'a> core::iter::Product<&'a #enum_name> for #enum_name {
+                    fn product<I: core::iter::Iterator<Item = &'a Self>>(iter: I) -> Self {
+                        Self::from_num(iter.fold(Self::MASK, |n, flag| n & flag.as_num()))
+                    }
+                }
+
+                #default_impl
+
+                impl core::str::FromStr for #enum_name {
+                    type Err = #krate::ParseFlagsError;
+
+                    /// Parses flag names separated by `|`, e.g. `"A | C"`, falling back to the raw value,
+                    /// e.g. `"5"`, `"0x5"` or `"0b101"`.
+                    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+                        #from_str.or_else(|e| Self::from_num_str(s).ok_or(e))
+                    }
+                }
+
+                impl core::convert::TryFrom<&str> for #enum_name {
+                    type Error = #krate::ParseFlagsError;
+
+                    /// Same as `FromStr`.
+                    #[inline]
+                    fn try_from(s: &str) -> core::result::Result<Self, Self::Error> {
+                        core::str::FromStr::from_str(s)
+                    }
+                }
+
+                #serde_impl
+
+                #clap_impl
+
+                impl core::cmp::PartialEq for #enum_name {
+                    #[inline]
+                    fn eq(&self, other: &Self) -> bool {
+                        self.as_num() == other.as_num()
+                    }
+                }
+
+                /// Hashes the known bits widened to a `u64` (`u128` for 128 bit reprs),
+                /// so the hash doesn't depend on the width of the declared repr.
+                impl core::hash::Hash for #enum_name {
+                    #[inline]
+                    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                        ((self.as_num() & Self::MASK) as #unsigned as #wide).hash(state)
+                    }
+                }
+
+                impl core::cmp::PartialEq<&#enum_name> for #enum_name {
+                    #[inline]
+                    fn eq(&self, other: &&#enum_name) -> bool {
+                        self.as_num() == other.as_num()
+                    }
+                }
+
+                impl core::cmp::PartialEq<#enum_name> for &#enum_name {
+                    #[inline]
+                    fn eq(&self, other: &#enum_name) -> bool {
+                        self.as_num() == other.as_num()
+                    }
+                }
+
+                impl core::cmp::PartialEq<#num> for #enum_name {
+                    #[inline]
+                    fn eq(&self, other: &#num) -> bool {
+                        self.as_num() == *other
+                    }
+                }
+
+                impl core::cmp::PartialEq<#enum_name> for #num {
+                    #[inline]
+                    fn eq(&self, other: &#enum_name) -> bool {
+                        *self == other.as_num()
+                    }
+                }
+            };
+
+            // a distinct type for masks, so they can't be mixed up with values
+            let (mask_type, mask_impls) = if options.with_mask {
+                let mask_name = format_ident!("{}Mask", enum_name);
+                let mask_doc = format!("A mask applied to [`{}`] values, see `apply`.", enum_name);
+                (
+                    quote! {
+                        #[doc = #mask_doc]
+                        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+                        #vis struct #mask_name(#num);
+                    },
+                    quote! {
+                        impl #mask_name {
+                            /// Creates a mask keeping the flags in `flags`.
+                            #[inline]
+                            #vis const fn new(flags: #enum_name) -> Self {
+                                #mask_name(flags.as_num())
+                            }
+
+                            /// Returns the raw mask.
+                            #[inline]
+                            #vis const fn bits(self) -> #num {
+                                self.0
+                            }
+
+                            /// Keeps the flags of `value` within the mask, i.e. `value & mask`.
+                            #[inline]
+                            #vis fn apply(self, value: #enum_name) -> #enum_name {
+                                #enum_name::from_num(value.as_num() & self.0)
+                            }
+                        }
+
+                        impl From<#enum_name> for #mask_name {
+                            #[inline]
+                            fn from(flags: #enum_name) -> Self {
+                                #mask_name::new(flags)
+                            }
+                        }
+
+                        impl core::ops::BitOr for #mask_name {
+                            type Output = Self;
+                            #[inline]
+                            fn bitor(self, rhs: Self) -> Self {
+                                #mask_name(self.0 | rhs.0)
+                            }
+                        }
+
+                        impl core::ops::BitAnd for #mask_name {
+                            type Output = Self;
+                            #[inline]
+                            fn bitand(self, rhs: Self) -> Self {
+                                #mask_name(self.0 & rhs.0)
+                            }
+                        }
+
+                        impl core::ops::Not for #mask_name {
+                            type Output = Self;
+                            #[inline]
+                            fn not(self) -> Self {
+                                #mask_name(!self.0)
+                            }
+                        }
+                    },
+                )
+            } else {
+                (quote! {}, quote! {})
+            };
+
+            // the impls refer to every variant, which mustn't warn if one is `#[deprecated]`,
+            // and the accessor names derived from unusual variant names may not be snake case
+            quote! {
+                #ast
+
+                #mask_type
+
+                #[allow(deprecated, non_snake_case)]
+                const _: () = {
+                    #impls
+
+                    #mask_impls
+                };
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    Ok(result)
+}
+
+fn max_value(num: &Ident) -> u128 {
+    match num.to_string().as_str() {
+        "u8" => u8::MAX as u128,
+        "u16" => u16::MAX as u128,
+        "u32" => u32::MAX as u128,
+        "u64" | "usize" => u64::MAX as u128,
+        "i8" => i8::MAX as u128,
+        "i16" => i16::MAX as u128,
+        "i32" => i32::MAX as u128,
+        "i64" | "isize" => i64::MAX as u128,
+        _ => u128::MAX,
+    }
+}
+
+/// Returns the narrowest repr of the same signedness as `num` that holds `largest`,
+/// if it's narrower than `num`. `usize` and `isize` count as 64 bits wide.
+fn narrower_repr(num: &Ident, largest: u128) -> Option<&'static str> {
+    let reprs: [(&str, u128); 5] = if num.to_string().starts_with('i') {
+        [
+            ("i8", i8::MAX as u128),
+            ("i16", i16::MAX as u128),
+            ("i32", i32::MAX as u128),
+            ("i64", i64::MAX as u128),
+            ("i128", i128::MAX as u128),
+        ]
+    } else {
+        [
+            ("u8", u8::MAX as u128),
+            ("u16", u16::MAX as u128),
+            ("u32", u32::MAX as u128),
+            ("u64", u64::MAX as u128),
+            ("u128", u128::MAX),
+        ]
+    };
+    let (repr, max) = reprs.iter().find(|(_, max)| largest <= *max)?;
+    if *max < max_value(num) && num != repr {
+        Some(repr)
+    } else {
+        None
+    }
+}
+
+const INT_TYPES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// The associated consts generated for every enum, which a group can't be named after.
+const GENERATED_CONSTS: [&str; 5] = ["MASK", "ALL", "COUNT", "FLAGS", "BITS"];
+
+/// The inherent methods generated with any option or feature, which a getter can't be named after.
+const INHERENT_METHODS: [&str; 88] = [
+    "all_of", "any_of", "apply", "as_num", "bits", "clear", "collect_into", "contains",
+    "contains_const", "debug_assert_valid", "describe", "difference", "fits_in_bits", "flag_at",
+    "flip", "for_each_flag", "from_be_bytes", "from_bits", "from_bits_retain", "from_bits_truncate",
+    "from_bools", "from_discriminants", "from_fn", "from_le_bytes", "from_name", "from_names",
+    "from_num", "from_num_str", "from_raw", "from_wide", "has_flag", "has_stray_bits", "index_of",
+    "insert", "insert_name", "intersection", "intersection_all", "into_raw", "invert_delta",
+    "invert_known", "is_all", "is_all_const", "is_clear", "is_empty", "is_empty_const",
+    "is_exactly_one_bit", "is_single", "iter", "iter_all_bits", "iter_named", "merge_with",
+    "missing", "modify", "name", "names", "next", "normalize", "normalized", "or_opt", "parse_csv",
+    "parse_ops", "parse_with", "possible_values", "prev", "remove", "replace", "rotate_left", "set",
+    "set_all", "set_count", "split", "symmetric_difference", "symmetric_difference_known",
+    "to_be_bytes", "to_bools", "to_le_bytes", "to_vec", "toggle", "toggle_all", "try_from_i64",
+    "try_from_names", "try_single", "union", "union_all", "unset_all", "unset_count", "variants",
+    "when",
+];
+
+/// Removes the `#[flag(...)]` attributes of a variant, returning the `rename` name if any.
+fn take_rename(attrs: &mut Vec<Attribute>) -> syn::Result<Option<syn::LitStr>> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("flag")) {
+        let nested = match attr.parse_meta()? {
+            Meta::List(list) => list.nested,
+            meta => return Err(syn::Error::new_spanned(meta, "expected `#[flag(rename = \"...\")]`")),
+        };
+        for arg in nested {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => match nv.lit {
+                    Lit::Str(s) => rename = Some(s),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                },
+                arg => return Err(syn::Error::new_spanned(arg, "unknown `flag` option")),
+            }
+        }
+    }
+    attrs.retain(|attr| !attr.path.is_ident("flag"));
+    Ok(rename)
+}
+
+fn extract_repr(attrs: &[Attribute]) -> Option<Ident> {
+    attrs
+        .iter()
+        .find_map(|attr| match attr.parse_meta() {
+            Err(why) => panic!("{:?}", syn::Error::new_spanned(
+                attr,
+                format!("Couldn't parse attribute: {}", why),
+            )),
+            Ok(Meta::List(ref meta)) if meta.path.is_ident("repr") => {
+                meta.nested.iter().find_map(|mi| match mi {
+                    NestedMeta::Meta(Meta::Path(path)) => path
+                        .get_ident()
+                        .filter(|ident| INT_TYPES.iter().any(|t| ident == t))
+                        .cloned(),
+                    _ => None,
+                })
+            }
+            Ok(_) => None,
+        })
+}
+
+fn extract_derives(attrs: &[Attribute]) -> Vec<Ident> {
+    attrs
+        .iter()
+        .flat_map(|attr| attr.parse_meta())
+        .flat_map(|ref meta| match meta {
+            Meta::List(ref meta) if meta.path.is_ident("derive") => {
+                meta.nested.iter().filter_map(|mi| match mi {
+                    NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned(),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+            }
+            _ => Default::default(),
+        })
+        .collect::<Vec<_>>()
+}
+
+fn strip_derive(attrs: &mut [Attribute], name: &str) {
+    for attr in attrs.iter_mut().filter(|attr| attr.path.is_ident("derive")) {
+        if let Ok(Meta::List(meta)) = attr.parse_meta() {
+            let is_name =
+                |mi: &&NestedMeta| matches!(mi, NestedMeta::Meta(Meta::Path(path)) if path.is_ident(name));
+            if meta.nested.iter().any(|mi| is_name(&mi)) {
+                let rest = meta.nested.iter().filter(|mi| !is_name(mi));
+                attr.tokens = quote! { (#(#rest),*) };
+            }
+        }
+    }
+}
+
+fn discriminant_value(expr: &Expr) -> Option<u128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(ref lit_int),
+            ..
+        }) => lit_int.base10_parse::<u128>().ok(),
+        _ => None,
+    }
+}
+
+fn to_snake_case(str: &str) -> String {
+    let mut s = String::with_capacity(str.len());
+    for (i, char) in str.char_indices() {
+        if char.is_uppercase() && char.is_ascii_alphabetic() {
+            if i > 0 {
+                s.push('_');
+            }
+            s.push(char.to_ascii_lowercase());
+        } else {
+            s.push(char)
+        }
+    }
+    s
+}
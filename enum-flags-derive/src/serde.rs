@@ -0,0 +1,240 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Lit, Meta, Path};
+
+/// How the flags are represented by the generated serde impls.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum SerdeMode {
+    /// The raw integer, e.g. `5`.
+    Bits,
+    /// A sequence of the contained flag names, e.g. `["A", "C"]`.
+    Names,
+    /// A map from every flag name to whether it's set, e.g. `{"A": true, "B": false, "C": true}`.
+    Map,
+    /// Serialized like `Names`, deserialized from either the raw integer or a sequence of names,
+    /// for migrating from `Bits`.
+    Flexible,
+}
+
+impl SerdeMode {
+    /// Parses `serde` or `serde = "bits" | "names" | "map" | "flexible"`.
+    pub(crate) fn parse(meta: &Meta) -> syn::Result<SerdeMode> {
+        match meta {
+            Meta::Path(_) => Ok(SerdeMode::Bits),
+            Meta::NameValue(nv) => match &nv.lit {
+                Lit::Str(s) if s.value() == "bits" => Ok(SerdeMode::Bits),
+                Lit::Str(s) if s.value() == "names" => Ok(SerdeMode::Names),
+                Lit::Str(s) if s.value() == "map" => Ok(SerdeMode::Map),
+                Lit::Str(s) if s.value() == "flexible" => Ok(SerdeMode::Flexible),
+                lit => Err(syn::Error::new_spanned(
+                    lit,
+                    "expected `\"bits\"`, `\"names\"`, `\"map\"` or `\"flexible\"`",
+                )),
+            },
+            Meta::List(_) => Err(syn::Error::new_spanned(
+                meta,
+                "expected `serde` or `serde = \"...\"`",
+            )),
+        }
+    }
+}
+
+pub(crate) fn impl_serde(krate: &Path, enum_name: &Ident, num: &Ident, mode: SerdeMode) -> TokenStream {
+    let serde = quote!(#krate::__private::serde);
+
+    // the flexible representation also accepts the raw integer
+    let (int_visits, deserialize_fn) = if mode == SerdeMode::Flexible {
+        (
+            quote! {
+                fn visit_u64<E: #serde::de::Error>(self, v: u64) -> core::result::Result<#enum_name, E> {
+                    match <#num as core::convert::TryFrom<u64>>::try_from(v) {
+                        core::result::Result::Ok(n) => core::result::Result::Ok(#enum_name::from_num(n)),
+                        core::result::Result::Err(_) => core::result::Result::Err(
+                            E::invalid_value(#serde::de::Unexpected::Unsigned(v), &self),
+                        ),
+                    }
+                }
+
+                fn visit_i64<E: #serde::de::Error>(self, v: i64) -> core::result::Result<#enum_name, E> {
+                    match <#num as core::convert::TryFrom<i64>>::try_from(v) {
+                        core::result::Result::Ok(n) => core::result::Result::Ok(#enum_name::from_num(n)),
+                        core::result::Result::Err(_) => core::result::Result::Err(
+                            E::invalid_value(#serde::de::Unexpected::Signed(v), &self),
+                        ),
+                    }
+                }
+            },
+            quote!(deserialize_any),
+        )
+    } else {
+        (quote! {}, quote!(deserialize_seq))
+    };
+    let expecting = if mode == SerdeMode::Flexible {
+        "an integer or a sequence of flag names"
+    } else {
+        "a sequence of flag names"
+    };
+
+    let (serialize, deserialize) = match mode {
+        SerdeMode::Bits => (
+            quote! {
+                #serde::Serialize::serialize(&self.as_num(), serializer)
+            },
+            quote! {
+                let n = <#num as #serde::Deserialize>::deserialize(deserializer)?;
+                core::result::Result::Ok(#enum_name::from_num(n))
+            },
+        ),
+        SerdeMode::Names | SerdeMode::Flexible => (
+            quote! {
+                use #serde::ser::SerializeSeq;
+                let mut seq = serializer.serialize_seq(core::option::Option::Some(self.iter().count()))?;
+                for (_, name) in self.iter_named() {
+                    seq.serialize_element(name)?;
+                }
+                seq.end()
+            },
+            quote! {
+                struct FlagName(#num);
+
+                impl<'de> #serde::Deserialize<'de> for FlagName {
+                    fn deserialize<D: #serde::Deserializer<'de>>(
+                        deserializer: D,
+                    ) -> core::result::Result<Self, D::Error> {
+                        struct NameVisitor;
+
+                        impl<'de> #serde::de::Visitor<'de> for NameVisitor {
+                            type Value = FlagName;
+
+                            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                                f.write_str("a flag name")
+                            }
+
+                            fn visit_str<E: #serde::de::Error>(self, v: &str) -> core::result::Result<FlagName, E> {
+                                match #enum_name::from_name(v) {
+                                    core::option::Option::Some(flag) => core::result::Result::Ok(FlagName(flag.as_num())),
+                                    core::option::Option::None => core::result::Result::Err(
+                                        E::unknown_variant(v, #enum_name::names()),
+                                    ),
+                                }
+                            }
+                        }
+
+                        deserializer.deserialize_str(NameVisitor)
+                    }
+                }
+
+                struct NamesVisitor;
+
+                impl<'de> #serde::de::Visitor<'de> for NamesVisitor {
+                    type Value = #enum_name;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        f.write_str(#expecting)
+                    }
+
+                    fn visit_seq<A: #serde::de::SeqAccess<'de>>(
+                        self,
+                        mut seq: A,
+                    ) -> core::result::Result<#enum_name, A::Error> {
+                        let mut n: #num = 0;
+                        while let core::option::Option::Some(FlagName(flag)) = seq.next_element()? {
+                            n |= flag;
+                        }
+                        core::result::Result::Ok(#enum_name::from_num(n))
+                    }
+
+                    #int_visits
+                }
+
+                deserializer.#deserialize_fn(NamesVisitor)
+            },
+        ),
+        SerdeMode::Map => (
+            quote! {
+                use #serde::ser::SerializeMap;
+                let mut map = serializer.serialize_map(core::option::Option::Some(#enum_name::variants().len()))?;
+                for (flag, name) in #enum_name::variants().iter().zip(#enum_name::names().iter()) {
+                    map.serialize_entry(name, &self.contains(#enum_name::from_num(flag.as_num())))?;
+                }
+                map.end()
+            },
+            quote! {
+                struct FlagKey(#num);
+
+                impl<'de> #serde::Deserialize<'de> for FlagKey {
+                    fn deserialize<D: #serde::Deserializer<'de>>(
+                        deserializer: D,
+                    ) -> core::result::Result<Self, D::Error> {
+                        struct KeyVisitor;
+
+                        impl<'de> #serde::de::Visitor<'de> for KeyVisitor {
+                            type Value = FlagKey;
+
+                            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                                f.write_str("a flag name")
+                            }
+
+                            fn visit_str<E: #serde::de::Error>(self, v: &str) -> core::result::Result<FlagKey, E> {
+                                match #enum_name::names().iter().position(|name| *name == v) {
+                                    core::option::Option::Some(i) => {
+                                        core::result::Result::Ok(FlagKey(#enum_name::variants()[i].as_num()))
+                                    }
+                                    core::option::Option::None => core::result::Result::Err(
+                                        E::unknown_field(v, #enum_name::names()),
+                                    ),
+                                }
+                            }
+                        }
+
+                        deserializer.deserialize_str(KeyVisitor)
+                    }
+                }
+
+                struct MapVisitor;
+
+                impl<'de> #serde::de::Visitor<'de> for MapVisitor {
+                    type Value = #enum_name;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        f.write_str("a map of flag names to booleans")
+                    }
+
+                    fn visit_map<A: #serde::de::MapAccess<'de>>(
+                        self,
+                        mut map: A,
+                    ) -> core::result::Result<#enum_name, A::Error> {
+                        let mut n: #num = 0;
+                        while let core::option::Option::Some(FlagKey(flag)) = map.next_key()? {
+                            if map.next_value::<bool>()? {
+                                n |= flag;
+                            }
+                        }
+                        core::result::Result::Ok(#enum_name::from_num(n))
+                    }
+                }
+
+                deserializer.deserialize_map(MapVisitor)
+            },
+        ),
+    };
+
+    quote! {
+        impl #serde::Serialize for #enum_name {
+            fn serialize<S: #serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                #serialize
+            }
+        }
+
+        impl<'de> #serde::Deserialize<'de> for #enum_name {
+            fn deserialize<D: #serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> core::result::Result<Self, D::Error> {
+                #deserialize
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+/// An entry yielded by `iter_all_bits`: a named flag, or a set bit no flag covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitEntry<T> {
+    /// A named flag contained in the value.
+    Named(T),
+    /// The index of a stray bit, counted from the least significant one.
+    Unknown(u32),
+}
@@ -0,0 +1,17 @@
+/// A change to a set of flags, e.g. for an undo history: the flags to set and the flags to clear.
+///
+/// `apply` sets `to_set` first, then clears `to_clear`, so a flag in both ends up cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FlagDelta<T> {
+    /// The flags to set.
+    pub to_set: T,
+    /// The flags to clear.
+    pub to_clear: T,
+}
+
+impl<T> FlagDelta<T> {
+    /// Creates a delta setting `to_set` and clearing `to_clear`.
+    pub const fn new(to_set: T, to_clear: T) -> Self {
+        FlagDelta { to_set, to_clear }
+    }
+}
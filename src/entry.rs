@@ -0,0 +1,44 @@
+use core::ops::{BitAnd, BitAndAssign, BitOrAssign, BitXorAssign, Not};
+
+/// A mutable view on one flag of a value returned by `modify`, e.g.
+/// `flags.modify(Flags::A).set()`.
+#[derive(Debug)]
+pub struct FlagEntry<'a, T> {
+    value: &'a mut T,
+    flag: T,
+}
+
+impl<'a, T> FlagEntry<'a, T> {
+    /// Creates an entry for `flag` in `value`.
+    pub fn new(value: &'a mut T, flag: T) -> Self {
+        FlagEntry { value, flag }
+    }
+}
+
+impl<T> FlagEntry<'_, T>
+where
+    T: Clone + PartialEq + BitAnd<Output = T> + Not<Output = T> + BitOrAssign + BitAndAssign + BitXorAssign,
+{
+    /// Returns `true` if all of the bits of the flag are set.
+    pub fn is_set(&self) -> bool {
+        self.value.clone() & self.flag.clone() == self.flag
+    }
+
+    /// Sets the flag.
+    pub fn set(&mut self) -> &mut Self {
+        *self.value |= self.flag.clone();
+        self
+    }
+
+    /// Clears the flag.
+    pub fn clear(&mut self) -> &mut Self {
+        *self.value &= !self.flag.clone();
+        self
+    }
+
+    /// Toggles the flag.
+    pub fn toggle(&mut self) -> &mut Self {
+        *self.value ^= self.flag.clone();
+        self
+    }
+}
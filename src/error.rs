@@ -0,0 +1,147 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// An error returned when parsing flags from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFlagsError {
+    #[cfg(feature = "alloc")]
+    token: String,
+}
+
+impl ParseFlagsError {
+    #[doc(hidden)]
+    pub fn new(token: &str) -> Self {
+        #[cfg(not(feature = "alloc"))]
+        let _ = token;
+        ParseFlagsError {
+            #[cfg(feature = "alloc")]
+            token: token.into(),
+        }
+    }
+
+    /// Returns the first token which isn't a flag name.
+    #[cfg(feature = "alloc")]
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl fmt::Display for ParseFlagsError {
+    #[cfg(feature = "alloc")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown flag `{}`", self.token)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown flag")
+    }
+}
+
+/// An error returned when a value has bits set beyond the width of the flags' repr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncationError {
+    value: u64,
+    bits: u32,
+}
+
+impl TruncationError {
+    #[doc(hidden)]
+    pub fn new(value: u64, bits: u32) -> Self {
+        TruncationError { value, bits }
+    }
+
+    /// Returns the value which didn't fit.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+impl fmt::Display for TruncationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value `{:#x}` doesn't fit in {} bits", self.value, self.bits)
+    }
+}
+
+/// An error returned when a value is expected to be exactly one named flag,
+/// but is empty or composed of several flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotSingleFlagError;
+
+impl fmt::Display for NotSingleFlagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value isn't a single named flag")
+    }
+}
+
+/// An error returned by `try_single` when a value is empty or holds several named flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiFlagError {
+    count: usize,
+}
+
+impl MultiFlagError {
+    #[doc(hidden)]
+    pub fn new(count: usize) -> Self {
+        MultiFlagError { count }
+    }
+
+    /// Returns how many named flags the value holds, zero if it's empty.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl fmt::Display for MultiFlagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected a single named flag, found {}", self.count)
+    }
+}
+
+/// An error returned when converting from an integer which doesn't fit in the repr,
+/// or which has bits set that don't belong to a named flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromIntError {
+    value: i64,
+    bits: u32,
+    stray: u64,
+}
+
+impl TryFromIntError {
+    #[doc(hidden)]
+    pub fn out_of_range(value: i64, bits: u32) -> Self {
+        TryFromIntError { value, bits, stray: 0 }
+    }
+
+    #[doc(hidden)]
+    pub fn stray_bits(value: i64, stray: u64) -> Self {
+        TryFromIntError { value, bits: 0, stray }
+    }
+
+    /// Returns the value which couldn't be converted.
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    /// Returns `true` if the value doesn't fit in the repr.
+    pub fn is_out_of_range(&self) -> bool {
+        self.stray == 0
+    }
+
+    /// Returns the bits which don't belong to a named flag, zero if the value is out of range.
+    pub fn stray(&self) -> u64 {
+        self.stray
+    }
+}
+
+impl fmt::Display for TryFromIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_out_of_range() {
+            write!(f, "value `{}` doesn't fit in {} bits", self.value, self.bits)
+        } else {
+            write!(f, "value `{:#x}` has unknown bits `{:#x}`", self.value, self.stray)
+        }
+    }
+}
@@ -0,0 +1,12 @@
+/// The operation `merge_with` combines two values with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeOp {
+    /// `a | b`
+    Union,
+    /// `a & b`
+    Intersection,
+    /// `a & !b`
+    Difference,
+    /// `a ^ b`
+    Xor,
+}
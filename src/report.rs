@@ -0,0 +1,13 @@
+use alloc::vec::Vec;
+
+/// A structured description of a value returned by `describe`, e.g. for an admin page
+/// listing the current feature flags.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FlagReport<T, N> {
+    /// The named flags which are set, in declaration order.
+    pub set: Vec<T>,
+    /// The named flags which are unset, in declaration order.
+    pub unset: Vec<T>,
+    /// The set bits which don't belong to a named flag.
+    pub stray_bits: N,
+}
@@ -0,0 +1,64 @@
+//! Compile test: everything generated by `#[enum_flags]`, except the methods gated
+//! behind the `alloc` feature, has to build in a `#![no_std]` crate without `alloc`.
+//!
+//! Build it on its own so the features of the other workspace members aren't unified:
+//! `cargo build -p enum-flags-no-std`
+#![no_std]
+
+use core::fmt::{self, Write};
+use enum_flags::enum_flags;
+
+#[repr(u8)]
+#[enum_flags]
+#[derive(Copy, Clone, PartialEq)]
+pub enum Flags {
+    None = 0,
+    A = 1,
+    B = 2,
+    C = 4,
+}
+
+/// The formatting options, which change what `Debug` and `Display` write.
+#[repr(u16)]
+#[enum_flags(debug_num = "hex", debug_path, display_all = "ALL")]
+#[derive(Copy, Clone, PartialEq)]
+pub enum Formatted {
+    None = 0,
+    A = 1,
+    B = 2,
+}
+
+/// A `fmt::Write` into a fixed buffer.
+pub struct Buf {
+    bytes: [u8; 128],
+    len: usize,
+}
+
+impl Write for Buf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.bytes.len() {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+pub fn exercise(buf: &mut Buf) -> fmt::Result {
+    let mut e = Flags::A | Flags::C;
+    e.insert(Flags::B);
+    e.remove(Flags::C);
+    let _ = e.contains(Flags::A) && e.has_b() && !e.is_empty();
+    let _ = e.iter().count() + Flags::names().len();
+    if let Err(err) = "A | D".parse::<Flags>() {
+        write!(buf, "{} ", err)?;
+    }
+    let _ = Flags::from_bits(3).is_some();
+    let _ = Flags::from_names(["A", "C"]).is_ok();
+    let _ = Flags::from_bits_truncate(0xff).bits();
+    write!(buf, "{} {:?} {:x} {:#b}", e, e, e, e)?;
+    let f = Formatted::A | Formatted::B;
+    write!(buf, "{} {:?} {:#?} {:#X} {:o}", f, f, Formatted::None, f, f)
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "accessors")]
+
+use enum_flags::{enum_flags, FlagsAccessors};
+
+
+#[test]
+fn test_accessors(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Permissions{
+        None = 0,
+        Read = 1,
+        Write = 2,
+        Exec = 4
+    }
+
+    #[derive(FlagsAccessors)]
+    struct Settings {
+        #[flags(Read, Write)]
+        perms: Permissions,
+    }
+
+    let mut settings = Settings { perms: Permissions::Read };
+    assert!(settings.perms_read());
+    assert!(!settings.perms_write());
+
+    settings.set_perms_write(true);
+    settings.set_perms_read(false);
+    assert_eq!(settings.perms, Permissions::Write);
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "clap")]
+
+use clap::{Arg, ArgAction, Command, ValueEnum};
+use enum_flags::enum_flags;
+
+
+#[test]
+fn test_clap_value_enum(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let names = Flags::possible_values().map(|v| v.get_name().to_owned()).collect::<Vec<_>>();
+    assert_eq!(names, ["A", "B", "C"]);
+    assert_eq!(Flags::from_str("b", true), Ok(Flags::B));
+    assert!(Flags::from_str("A | B", false).is_err());
+    assert!((Flags::A | Flags::B).to_possible_value().is_none());
+
+    let cmd = Command::new("test").arg(
+        Arg::new("flag")
+            .long("flag")
+            .action(ArgAction::Append)
+            .value_parser(clap::value_parser!(Flags)),
+    );
+    let matches = cmd.clone().try_get_matches_from(["test", "--flag", "A", "--flag", "C"]).unwrap();
+    let flags: Flags = matches.get_many::<Flags>("flag").unwrap_or_default().sum();
+    assert_eq!(flags, Flags::A | Flags::C);
+
+    assert!(cmd.try_get_matches_from(["test", "--flag", "D"]).is_err());
+}
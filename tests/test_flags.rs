@@ -1,5 +1,3 @@
-#![feature(arbitrary_enum_discriminant)]
-
 use enum_flags::enum_flags;
 
 
@@ -58,7 +56,8 @@ fn test_has_flag(){
     assert!(s.has_flag(State::B));
     assert!(s.has_b());
     assert!(!s.has_flag(State::C));
-    assert!(!s.has_flag(State::None));
+    assert!(s.has_flag(State::None));
+    assert!(!s.has_none());
 }
 
 #[test]
@@ -254,12 +253,15 @@ fn test_contains(){
     assert!(e1.contains(Flags::A));
 
     let e1 = Flags::A | Flags::C;
-    assert!(!e1.contains(Flags::None));
+    assert!(e1.contains(Flags::None));
+    assert!(e1.contains(Flags::A | Flags::C));
+    assert!(!e1.contains(Flags::A | Flags::B));
 
 
 
     let e1 = Flags::None;
     assert!(e1.contains(Flags::None));
+    assert!(!e1.contains(Flags::A));
 }
 
 
@@ -357,4 +359,2198 @@ fn test_omit_derives(){
     let mut e1 = Flags::A;
     e1.insert(Flags::B);
     assert_eq!("(Flags::A | Flags::B)", format!("{:?}", e1));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_normalize(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::from_num(0xFF).normalized(), Flags::ALL);
+
+    let mut e1 = Flags::from_num(0x09);
+    e1.normalize();
+    assert_eq!(e1, Flags::A);
+}
+
+
+#[test]
+fn test_hex(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!("5", format!("{:x}", e1));
+    assert_eq!("101", format!("{:b}", e1));
+    assert_eq!("Flags(0x5)", format!("{:#x}", e1));
+    assert_eq!("Flags(0x05)", format!("{:#04x}", e1));
+    assert_eq!("(Flags::A | Flags::C)", format!("{:x?}", e1));
+}
+
+
+#[test]
+fn test_alternate_debug(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Settings{
+        f: Flags
+    }
+
+    assert_eq!("(Flags::A | Flags::C)", format!("{:#?}", Flags::A | Flags::C));
+    assert_eq!("Settings {\n    f: (Flags::A | Flags::C),\n}", format!("{:#?}", Settings { f: Flags::A | Flags::C }));
+}
+
+
+#[test]
+fn test_bools(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(e1.to_bools(), [true, false, true]);
+    assert_eq!(Flags::from_bools([true, false, true]), e1);
+    assert_eq!(Flags::from_bools([false; 3]), Flags::None);
+    assert_eq!(Flags::variants(), &[Flags::A, Flags::B, Flags::C]);
+}
+
+
+#[test]
+fn test_repr_c(){
+    #[repr(C, u16)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x100
+    }
+
+    let e1 = Flags::A | Flags::C;
+    let n: u16 = e1.as_num();
+    assert_eq!(n, 0x101);
+    assert_eq!(Flags::from_num(0x101), e1);
+    assert_eq!("(Flags::A | Flags::C)", format!("{:?}", e1));
+}
+
+
+#[test]
+fn test_from_name(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::from_name("B"), Some(Flags::B));
+    assert_eq!(Flags::from_name("D"), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_try_from_names(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::try_from_names(vec!["A", "C"]), Ok(Flags::A | Flags::C));
+    assert_eq!(
+        Flags::try_from_names(vec!["A", "D", "C", "E"]),
+        Err(vec!["D".to_string(), "E".to_string()])
+    );
+}
+
+
+#[test]
+fn test_bits(){
+    #[repr(u8)]
+    #[enum_flags]
+    enum Flags{
+        None = 0,
+        A = 1,
+    }
+
+    #[repr(u32)]
+    #[enum_flags]
+    enum WideFlags{
+        None = 0,
+        A = 1,
+    }
+
+    #[enum_flags]
+    enum DefaultFlags{
+        None = 0,
+        A = 1,
+    }
+
+    assert_eq!(Flags::BITS, 8);
+    assert_eq!(WideFlags::BITS, 32);
+    assert_eq!(DefaultFlags::BITS, usize::BITS);
+}
+
+
+#[test]
+fn test_no_copy(){
+    #[repr(u128)]
+    #[enum_flags(no_copy)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A | Flags::C;
+    e1.insert(Flags::B);
+    e1.remove(Flags::C);
+    assert_eq!(e1.clone(), Flags::A | Flags::B);
+    assert_eq!(&e1 | &Flags::C, Flags::ALL);
+    assert_eq!(&e1 & &Flags::B, Flags::B);
+    assert_eq!(&e1 - &Flags::B, Flags::A);
+    assert_eq!(&e1 + &Flags::C, Flags::ALL);
+    assert!(!e1.is_all());
+    assert_eq!(e1.union(Flags::C), Flags::ALL);
+}
+
+
+#[test]
+fn test_set_all(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::from_num(0x88);
+    e1.set_all();
+    assert_eq!(e1, Flags::ALL);
+    assert_eq!(e1.as_num(), 0x07);
+    e1.unset_all();
+    assert_eq!(e1, Flags::None);
+}
+
+
+#[test]
+fn test_iter_named(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::C | Flags::A;
+    assert_eq!(e1.iter_named().collect::<Vec<_>>(), vec![(Flags::A, "A"), (Flags::C, "C")]);
+    assert_eq!(e1.iter().collect::<Vec<_>>(), vec![Flags::A, Flags::C]);
+    assert_eq!(Flags::None.iter().count(), 0);
+    assert_eq!(Flags::names(), &["A", "B", "C"]);
+    assert_eq!(Flags::B.name(), Some("B"));
+    assert_eq!(e1.name(), None);
+}
+
+
+#[test]
+fn test_no_accessors(){
+    #[repr(u8)]
+    #[enum_flags(no_accessors)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert!(e1.contains(Flags::A));
+    assert!(!e1.has_flag(Flags::B));
+    assert_eq!("(Flags::A | Flags::C)", format!("{:?}", e1));
+}
+
+
+#[test]
+fn test_option_accessors(){
+    #[repr(u8)]
+    #[enum_flags(accessor_kind = "option")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        ReadOnly = 2,
+        Type = 4
+    }
+
+    let e1 = Flags::A | Flags::Type;
+    assert_eq!(e1.a(), Some(Flags::A));
+    assert_eq!(e1.read_only(), None);
+    assert_eq!(e1.r#type(), Some(Flags::Type));
+    assert_eq!(e1.a().map(|flag| flag.name()), Some(Some("A")));
+}
+
+
+#[test]
+fn test_single(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert!(Flags::A.is_single());
+    assert!(Flags::A.is_exactly_one_bit());
+    assert!(!(Flags::A | Flags::B).is_single());
+    assert!(!(Flags::A | Flags::B).is_exactly_one_bit());
+    assert!(!Flags::None.is_single());
+    assert!(!Flags::None.is_exactly_one_bit());
+
+    let e1 = Flags::from_num(0x09);
+    assert!(e1.is_single());
+    assert!(!e1.is_exactly_one_bit());
+
+    let e1 = Flags::from_num(0x08);
+    assert!(!e1.is_single());
+    assert!(e1.is_exactly_one_bit());
+}
+
+
+#[test]
+fn test_zero_variant_name(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        A = 1,
+        B = 2,
+        Disabled = 0,
+        C = 4
+    }
+
+    assert_eq!(Flags::default(), Flags::Disabled);
+    assert_eq!(Flags::A & Flags::B, Flags::Disabled);
+    assert_eq!("(Flags::Disabled)", format!("{:?}", Flags::default()));
+    assert_eq!(Flags::variants(), &[Flags::A, Flags::B, Flags::C]);
+
+    let mut e1 = Flags::A | Flags::C;
+    e1.clear();
+    assert!(e1.is_empty());
+    assert_eq!(e1, Flags::Disabled);
+}
+
+
+#[test]
+fn test_or_opt(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::or_opt(Some(Flags::A), Some(Flags::C)), Some(Flags::A | Flags::C));
+    assert_eq!(Flags::or_opt(Some(Flags::A), None), Some(Flags::A));
+    assert_eq!(Flags::or_opt(None, Some(Flags::B)), Some(Flags::B));
+    assert_eq!(Flags::or_opt(None, None), None);
+}
+
+
+#[test]
+fn test_from_bits(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const E1: Flags = Flags::from_bits_retain(0x0D);
+    assert_eq!(E1.bits(), 0x0D);
+    assert_eq!(Flags::from_bits_truncate(0x0D), Flags::A | Flags::C);
+    assert_eq!(Flags::from_bits(0x05), Some(Flags::A | Flags::C));
+    assert_eq!(Flags::from_bits(0x0D), None);
+}
+
+
+#[test]
+fn test_rotate_left(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x10
+    }
+
+    assert_eq!(Flags::A.rotate_left(1), Flags::B);
+    assert_eq!(Flags::B.rotate_left(1), Flags::C);
+    assert_eq!(Flags::C.rotate_left(1), Flags::A);
+    assert_eq!((Flags::A | Flags::C).rotate_left(1), Flags::A | Flags::B);
+    assert_eq!(Flags::A.rotate_left(3), Flags::A);
+    assert_eq!(Flags::None.rotate_left(1), Flags::None);
+    assert_eq!(Flags::from_num(0x81).rotate_left(2), Flags::C);
+    assert_eq!(Flags::C.rotate_left(u32::MAX), Flags::C);
+    assert_eq!((Flags::B | Flags::C).rotate_left(u32::MAX - 1), Flags::A | Flags::B);
+}
+
+
+#[test]
+fn test_from_str(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!("A | C".parse::<Flags>(), Ok(e1));
+    assert_eq!("A|C".parse::<Flags>(), Ok(e1));
+    assert_eq!("5".parse::<Flags>(), Ok(e1));
+    assert_eq!("0x5".parse::<Flags>(), Ok(e1));
+    assert_eq!("0b101".parse::<Flags>(), Ok(e1));
+    assert_eq!("".parse::<Flags>(), Ok(Flags::None));
+    assert_eq!("None".parse::<Flags>(), Ok(Flags::None));
+    assert!("A | D".parse::<Flags>().is_err());
+    assert!("0x".parse::<Flags>().is_err());
+    assert!("256".parse::<Flags>().is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_from_str_error(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+    }
+
+    let err = "A | D | E".parse::<Flags>().unwrap_err();
+    assert_eq!(err.token(), "D");
+    assert_eq!(err.to_string(), "unknown flag `D`");
+}
+
+
+#[test]
+fn test_collect_into(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::B | Flags::C;
+
+    let mut buf = [Flags::None; 4];
+    assert_eq!(e1.collect_into(&mut buf), 3);
+    assert_eq!(buf, [Flags::A, Flags::B, Flags::C, Flags::None]);
+
+    let mut buf = [Flags::None; 2];
+    assert_eq!(e1.collect_into(&mut buf), 2);
+    assert_eq!(buf, [Flags::A, Flags::B]);
+
+    assert_eq!(Flags::None.collect_into(&mut buf), 0);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_to_vec(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!((Flags::C | Flags::A).to_vec(), vec![Flags::A, Flags::C]);
+    assert!(Flags::None.to_vec().is_empty());
+}
+
+
+#[test]
+fn test_is_all_stray_bits(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert!(Flags::ALL.is_all());
+    assert!(Flags::from_num(0xFF).is_all());
+    assert!(!Flags::from_num(0xF3).is_all());
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum NoZero{
+        A = 1,
+        B = 2,
+    }
+
+    assert!((NoZero::A | NoZero::B).is_all());
+    assert!(!NoZero::A.is_all());
+}
+
+
+#[test]
+fn test_parse_csv(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::parse_csv("A,C"), Ok(Flags::A | Flags::C));
+    assert_eq!(Flags::parse_csv(" A , B "), Ok(Flags::A | Flags::B));
+    assert_eq!(Flags::parse_csv(""), Ok(Flags::None));
+    assert!(Flags::parse_csv("A | C").is_err());
+    #[cfg(feature = "alloc")]
+    assert_eq!(Flags::parse_csv("A,D,E").unwrap_err().token(), "D");
+}
+
+
+#[test]
+fn test_case_insensitive(){
+    #[repr(u8)]
+    #[enum_flags(case_insensitive)]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        Read = 1,
+        Write = 2,
+        Exec = 4
+    }
+
+    assert_eq!(Flags::from_name("rEaD"), Some(Flags::Read));
+    assert_eq!("read | WRITE".parse::<Flags>(), Ok(Flags::Read | Flags::Write));
+    assert_eq!(Flags::parse_csv("exec,Read"), Ok(Flags::Read | Flags::Exec));
+    assert!("reed".parse::<Flags>().is_err());
+    assert_eq!(Flags::Read.name(), Some("Read"));
+}
+
+
+#[test]
+fn test_raw_operands(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::A | 0x4, Flags::A | Flags::C);
+    assert_eq!(0x4 | Flags::A, Flags::A | Flags::C);
+    assert_eq!((Flags::A | Flags::C) & 0x4, Flags::C);
+    assert_eq!(0x5 & Flags::C, Flags::C);
+    assert_eq!(Flags::A ^ 0x3, Flags::B);
+    assert_eq!((Flags::A | 0x8).as_num(), 0x9);
+}
+
+
+#[test]
+fn test_from_wide(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x80
+    }
+
+    assert_eq!(Flags::from_wide(0x81), Ok(Flags::A | Flags::C));
+    let err = Flags::from_wide(0x181).unwrap_err();
+    assert_eq!(err.value(), 0x181);
+    assert_eq!(format!("{}", err), "value `0x181` doesn't fit in 8 bits");
+
+    #[repr(u128)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum WideFlags{
+        None = 0,
+        A = 1,
+    }
+
+    assert_eq!(WideFlags::from_wide(u64::MAX).map(|f| f.as_num()), Ok(u64::MAX as u128));
+
+    #[repr(i8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum SignedFlags{
+        None = 0,
+        A = 1,
+        B = -0x80
+    }
+
+    assert_eq!(SignedFlags::from_wide(0x80), Ok(SignedFlags::B));
+    assert_eq!(SignedFlags::from_wide(0xff).map(|f| f.as_num()), Ok(-1));
+    assert_eq!(SignedFlags::from_wide(0x100).unwrap_err().value(), 0x100);
+}
+
+
+#[test]
+fn test_sum(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let flags = [Flags::A, Flags::C, Flags::A];
+    assert_eq!(flags.iter().sum::<Flags>(), Flags::A | Flags::C);
+    assert_eq!(flags.iter().copied().sum::<Flags>(), Flags::A | Flags::C);
+    assert_eq!(core::iter::empty::<Flags>().sum::<Flags>(), Flags::None);
+}
+
+
+#[test]
+fn test_product(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let flags = [Flags::A | Flags::B, Flags::A | Flags::C, Flags::ALL];
+    assert_eq!(flags.iter().product::<Flags>(), Flags::A);
+    assert_eq!(flags.iter().copied().product::<Flags>(), Flags::A);
+    assert_eq!([Flags::B, Flags::C].iter().product::<Flags>(), Flags::None);
+    assert_eq!(core::iter::empty::<Flags>().product::<Flags>(), Flags::ALL);
+}
+
+
+#[test]
+fn test_display(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(format!("{}", Flags::A | Flags::C), "A | C");
+    assert_eq!(format!("{}", Flags::ALL), "A | B | C");
+    assert_eq!(format!("{}", Flags::None), "None");
+    assert_eq!(format!("{}", Flags::A | Flags::C).parse::<Flags>(), Ok(Flags::A | Flags::C));
+    assert_eq!(format!("{}", Flags::from_num(8)), "");
+    assert_eq!(format!("{:?}", Flags::from_num(8)), "()");
+    assert_eq!(format!("{:?}", Flags::None), "(Flags::None)");
+    assert_eq!(format!("{}", Flags::from_num(9)), "A");
+}
+
+#[test]
+fn test_display_all(){
+    #[repr(u8)]
+    #[enum_flags(display_all = "ALL")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(format!("{}", Flags::ALL), "ALL");
+    assert_eq!(format!("{}", Flags::A | Flags::B), "A | B");
+    assert_eq!("ALL".parse::<Flags>(), Ok(Flags::ALL));
+    assert_eq!(format!("{}", Flags::ALL).parse::<Flags>(), Ok(Flags::ALL));
+}
+
+
+macro_rules! wrapped_flags {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variant:ident = $value:expr),* $(,)? }) => {
+        #[enum_flags]
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant = $value),*
+        }
+    };
+}
+
+#[test]
+fn test_macro_rules_wrapper(){
+    wrapped_flags! {
+        #[repr(u8)]
+        #[derive(Copy, Clone, PartialEq)]
+        enum Flags {
+            None = 0,
+            A = 1,
+            B = 2,
+            C = 4,
+        }
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert!(e1.has_a());
+    assert_eq!(e1.as_num(), 5u8);
+    assert_eq!("(Flags::A | Flags::C)", format!("{:?}", e1));
+}
+
+mod reexport {
+    pub use enum_flags::*;
+}
+
+macro_rules! reexported_flags {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($body:tt)* }) => {
+        #[crate::reexport::enum_flags(crate = "crate::reexport")]
+        $(#[$meta])*
+        $vis enum $name {
+            $($body)*
+        }
+    };
+}
+
+#[test]
+fn test_crate_path(){
+    reexported_flags! {
+        #[repr(u8)]
+        enum Flags {
+            None = 0,
+            A = 1,
+            B = 2,
+        }
+    }
+
+    assert_eq!("A | B".parse::<Flags>(), Ok(Flags::A | Flags::B));
+}
+
+
+#[test]
+fn test_flip(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A | Flags::C;
+    e1.flip(Flags::A | Flags::B);
+    assert_eq!(e1, Flags::B | Flags::C);
+    e1.flip(Flags::A | Flags::B);
+    assert_eq!(e1, Flags::A | Flags::C);
+}
+
+
+#[test]
+fn test_count(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const COUNT: usize = Flags::COUNT;
+    let table: [&str; COUNT] = ["a", "b", "c"];
+    assert_eq!(table.len(), Flags::variants().len());
+}
+
+
+#[test]
+fn test_index_of(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::index_of(Flags::A), Some(0));
+    assert_eq!(Flags::index_of(Flags::C), Some(2));
+    assert_eq!(Flags::index_of(Flags::None), None);
+    assert_eq!(Flags::index_of(Flags::A | Flags::B), None);
+    assert_eq!(Flags::flag_at(1), Some(Flags::B));
+    assert_eq!(Flags::flag_at(Flags::COUNT), None);
+}
+
+
+#[test]
+fn test_as_num_const(){
+    #[repr(i16)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x4000
+    }
+
+    const C: i16 = Flags::C.as_num();
+    assert_eq!(C, 0x4000);
+    assert_eq!((Flags::A | Flags::C).as_num(), 0x4001);
+    assert_eq!(i16::from(Flags::B), 2);
+    assert_eq!(i16::from(&Flags::None), 0);
+}
+
+
+#[test]
+fn test_eq_composed(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::__Composed__(1), Flags::A);
+    assert_eq!(Flags::None, Flags::__Composed__(0));
+    assert_ne!(Flags::__Composed__(3), Flags::A);
+    assert_eq!(Flags::__Composed__(5), Flags::A | Flags::C);
+}
+
+
+#[test]
+fn test_try_into_str(){
+    use std::convert::TryFrom;
+    use enum_flags::NotSingleFlagError;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(<&str>::try_from(Flags::B), Ok("B"));
+    assert_eq!(<&str>::try_from(Flags::__Composed__(4)), Ok("C"));
+    assert_eq!(<&str>::try_from(Flags::A | Flags::C), Err(NotSingleFlagError));
+    assert_eq!(<&str>::try_from(Flags::None), Err(NotSingleFlagError));
+    assert_eq!(<&str>::try_from(Flags::from_bits_retain(9)), Err(NotSingleFlagError));
+}
+
+
+#[test]
+fn test_deprecated_variant(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        #[deprecated(note = "use `C`")]
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::from_bits_retain(2) | Flags::C;
+    assert_eq!(format!("{:?}", e1), "(Flags::B | Flags::C)");
+    assert_eq!(e1.to_string(), "B | C");
+    assert_eq!("B".parse::<Flags>().unwrap().bits(), 2);
+    #[allow(deprecated)]
+    {
+        assert!(e1.has_b());
+        assert!(e1.contains(Flags::B));
+    }
+}
+
+
+#[test]
+fn test_merge_with(){
+    use enum_flags::MergeOp;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::B;
+    let e2 = Flags::B | Flags::C;
+    assert_eq!(e1.merge_with(e2, MergeOp::Union), Flags::A | Flags::B | Flags::C);
+    assert_eq!(e1.merge_with(e2, MergeOp::Intersection), Flags::B);
+    assert_eq!(e1.merge_with(e2, MergeOp::Difference), Flags::A);
+    assert_eq!(e1.merge_with(e2, MergeOp::Xor), Flags::A | Flags::C);
+}
+
+
+#[test]
+fn test_split(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::B | Flags::C;
+    assert_eq!(e1.split(Flags::A | Flags::C), (Flags::A | Flags::C, Flags::B));
+    assert_eq!(e1.split(Flags::None), (Flags::None, e1));
+    assert_eq!(Flags::from_bits_retain(9).split(Flags::A), (Flags::A, Flags::from_bits_retain(8)));
+}
+
+
+#[test]
+fn test_hash(){
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash>(value: T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+    enum Small{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x80
+    }
+
+    #[repr(i32)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+    enum Wide{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x80
+    }
+
+    assert_eq!(hash(Small::A | Small::C), hash(Wide::A | Wide::C));
+    assert_eq!(hash(Small::__Composed__(1)), hash(Small::A));
+    assert_eq!(hash(Small::from_bits_retain(0x41)), hash(Small::A));
+    assert_eq!(hash(Small::None), hash(0u64));
+}
+
+
+#[test]
+fn test_flags_const(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const FLAGS: [(&str, Flags); Flags::COUNT] = Flags::FLAGS;
+    assert_eq!(FLAGS, [("A", Flags::A), ("B", Flags::B), ("C", Flags::C)]);
+    let map = Flags::FLAGS.iter().copied().collect::<std::collections::HashMap<_, _>>();
+    assert_eq!(map["B"], Flags::B);
+}
+
+
+#[test]
+fn test_add(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A + Flags::C;
+    assert_eq!(e1, Flags::A | Flags::C);
+    assert_eq!(e1 + Flags::A, e1);
+    e1 += Flags::B;
+    assert_eq!(e1 - Flags::A, Flags::B | Flags::C);
+}
+
+
+#[test]
+fn test_is_clear(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert!(e1.is_clear(Flags::B));
+    assert!(e1.is_clear(Flags::A | Flags::B));
+    assert!(!e1.is_clear(Flags::C));
+    assert!(!e1.is_clear(Flags::None));
+}
+
+
+#[test]
+fn test_try_from_str(){
+    use std::convert::{TryFrom, TryInto};
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::try_from("A | C"), Ok(Flags::A | Flags::C));
+    let e1: Flags = "0x2".try_into().unwrap();
+    assert_eq!(e1, Flags::B);
+    assert_eq!(Flags::try_from("D"), "D".parse::<Flags>());
+    assert!(Flags::try_from("D").is_err());
+}
+
+
+#[test]
+fn test_any_of(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert!(e1.any_of(&[Flags::B, Flags::C]));
+    assert!(!e1.any_of(&[Flags::B, Flags::A | Flags::B]));
+    assert!(!e1.any_of(&[]));
+    assert!(e1.all_of(&[Flags::A, Flags::C]));
+    assert!(!e1.all_of(&[Flags::A, Flags::B]));
+    assert!(e1.all_of(&[]));
+}
+
+
+#[test]
+fn test_missing(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4,
+        D = 8
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(e1.missing().collect::<Vec<_>>(), [Flags::B, Flags::D]);
+    assert_eq!(Flags::None.missing().count(), Flags::COUNT);
+    assert_eq!(Flags::ALL.missing().count(), 0);
+}
+
+
+#[test]
+fn test_when(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const E1: Flags = Flags::A.when(true);
+    assert_eq!(E1, Flags::A);
+    assert_eq!(Flags::A.when(true) | Flags::B.when(false) | Flags::C.when(true), Flags::A | Flags::C);
+    assert_eq!((Flags::A | Flags::B).when(false), Flags::None);
+}
+
+
+#[test]
+fn test_insert_name(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A;
+    assert_eq!(e1.insert_name("C"), Ok(()));
+    assert_eq!(e1.insert_name(" B "), Ok(()));
+    assert_eq!(e1, Flags::A | Flags::B | Flags::C);
+    let err = e1.insert_name("D").unwrap_err();
+    #[cfg(feature = "alloc")]
+    assert_eq!(err.token(), "D");
+    assert_eq!(err, "D".parse::<Flags>().unwrap_err());
+    assert_eq!(e1, Flags::A | Flags::B | Flags::C);
+}
+
+
+#[test]
+fn test_default_option(){
+    #[repr(u8)]
+    #[enum_flags(default = "A | C")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Empty{
+        None = 0,
+        A = 1
+    }
+
+    assert_eq!(Flags::default(), Flags::A | Flags::C);
+    assert_eq!(Empty::default(), Empty::None);
+}
+
+
+#[test]
+fn test_const_checks(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const _: () = assert!(Flags::ALL.is_all_const());
+    const _: () = assert!(!Flags::from_bits_retain(3).is_all_const());
+    const _: () = assert!(Flags::None.is_empty_const());
+    const _: () = assert!(!Flags::C.is_empty_const());
+    assert_eq!(Flags::from_bits_retain(0xff).is_all_const(), Flags::from_bits_retain(0xff).is_all());
+}
+
+
+#[test]
+fn test_64_variants(){
+    #[repr(u64)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        F0 = 0x1,
+        F1 = 0x2,
+        F2 = 0x4,
+        F3 = 0x8,
+        F4 = 0x10,
+        F5 = 0x20,
+        F6 = 0x40,
+        F7 = 0x80,
+        F8 = 0x100,
+        F9 = 0x200,
+        F10 = 0x400,
+        F11 = 0x800,
+        F12 = 0x1000,
+        F13 = 0x2000,
+        F14 = 0x4000,
+        F15 = 0x8000,
+        F16 = 0x10000,
+        F17 = 0x20000,
+        F18 = 0x40000,
+        F19 = 0x80000,
+        F20 = 0x100000,
+        F21 = 0x200000,
+        F22 = 0x400000,
+        F23 = 0x800000,
+        F24 = 0x1000000,
+        F25 = 0x2000000,
+        F26 = 0x4000000,
+        F27 = 0x8000000,
+        F28 = 0x10000000,
+        F29 = 0x20000000,
+        F30 = 0x40000000,
+        F31 = 0x80000000,
+        F32 = 0x100000000,
+        F33 = 0x200000000,
+        F34 = 0x400000000,
+        F35 = 0x800000000,
+        F36 = 0x1000000000,
+        F37 = 0x2000000000,
+        F38 = 0x4000000000,
+        F39 = 0x8000000000,
+        F40 = 0x10000000000,
+        F41 = 0x20000000000,
+        F42 = 0x40000000000,
+        F43 = 0x80000000000,
+        F44 = 0x100000000000,
+        F45 = 0x200000000000,
+        F46 = 0x400000000000,
+        F47 = 0x800000000000,
+        F48 = 0x1000000000000,
+        F49 = 0x2000000000000,
+        F50 = 0x4000000000000,
+        F51 = 0x8000000000000,
+        F52 = 0x10000000000000,
+        F53 = 0x20000000000000,
+        F54 = 0x40000000000000,
+        F55 = 0x80000000000000,
+        F56 = 0x100000000000000,
+        F57 = 0x200000000000000,
+        F58 = 0x400000000000000,
+        F59 = 0x800000000000000,
+        F60 = 0x1000000000000000,
+        F61 = 0x2000000000000000,
+        F62 = 0x4000000000000000,
+        F63 = 0x8000000000000000
+    }
+
+    let e1 = Flags::F0 | Flags::F31 | Flags::F63;
+    assert_eq!(Flags::COUNT, 64);
+    assert_eq!(Flags::MASK, u64::MAX);
+    assert!(Flags::ALL.is_all());
+    assert_eq!(e1.iter().count(), 3);
+    assert_eq!(format!("{:?}", e1), "(Flags::F0 | Flags::F31 | Flags::F63)");
+    assert_eq!(e1.to_string(), "F0 | F31 | F63");
+    assert_eq!("F0 | F31 | F63".parse::<Flags>(), Ok(e1));
+    assert!(e1.has_f31());
+    assert_eq!(Flags::from_bools(e1.to_bools()), e1);
+}
+
+
+#[test]
+fn test_from_names(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::from_names(["A", "C"]), Ok(Flags::A | Flags::C));
+    assert_eq!(Flags::from_names(std::iter::empty()), Ok(Flags::None));
+    let err = Flags::from_names(["A", "D", "C", "E"]).unwrap_err();
+    #[cfg(feature = "alloc")]
+    assert_eq!(err.token(), "D");
+    assert_eq!(err, "D".parse::<Flags>().unwrap_err());
+}
+
+
+#[test]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+fn test_unusual_variant_names(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        Read_Only = 1,
+        Ä = 2,
+        HTTP = 4
+    }
+
+    let e1 = Flags::Read_Only | Flags::HTTP;
+    assert!(e1.has_read__only());
+    assert!(!e1.has_Ä());
+    assert!(e1.has_h_t_t_p());
+}
+
+
+#[test]
+fn test_parse_with(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::parse_with("A + C", '+'), Ok(Flags::A | Flags::C));
+    assert!(Flags::parse_with("A  B\tC", ' ').is_err());
+    assert_eq!(Flags::parse_with(" A   C ", ' '), Ok(Flags::A | Flags::C));
+    assert_eq!(Flags::parse_with("A,,B,", ','), Ok(Flags::A | Flags::B));
+    assert_eq!(Flags::parse_with("", ','), Ok(Flags::None));
+    assert!(Flags::parse_with("A;D", ';').is_err());
+}
+
+
+#[test]
+fn test_set_count(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::from_bits_retain(0x85);
+    assert_eq!(e1.bits().count_ones(), 3);
+    assert_eq!(e1.set_count(), 2);
+    assert_eq!(e1.unset_count(), 1);
+    assert_eq!(Flags::None.unset_count(), 3);
+    assert_eq!(Flags::ALL.set_count(), 3);
+}
+
+
+#[test]
+fn test_from_discriminants(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const E1: Flags = Flags::from_discriminants(&[1, 4]);
+    assert_eq!(E1, Flags::A | Flags::C);
+    assert_eq!(Flags::from_discriminants(&[]), Flags::None);
+    assert_eq!(Flags::from_discriminants(&[2, 0x10]).bits(), 0x12);
+    assert_eq!(Flags::from_discriminants(&[2, 0x10]).normalized(), Flags::B);
+}
+
+
+#[test]
+fn test_debug_num(){
+    #[repr(u8)]
+    #[enum_flags(debug_num)]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    #[repr(u8)]
+    #[enum_flags(debug_num = "hex")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Hex{
+        None = 0,
+        A = 1,
+        B = 0x10
+    }
+
+    assert_eq!(format!("{:?}", Flags::A | Flags::C), "(Flags::A | Flags::C = 5)");
+    assert_eq!(format!("{:?}", Flags::None), "(Flags::None = 0)");
+    assert_eq!(format!("{:?}", Hex::A | Hex::B), "(Hex::A | Hex::B = 0x11)");
+    assert_eq!(format!("{:#?}", Hex::B), "(Hex::B = 0x10)");
+}
+
+
+#[test]
+fn test_try_from_i64(){
+    use std::convert::TryFrom;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::try_from(5i64), Ok(Flags::A | Flags::C));
+    assert_eq!(Flags::try_from(0i64), Ok(Flags::None));
+
+    let err = Flags::try_from(256i64).unwrap_err();
+    assert!(err.is_out_of_range());
+    assert_eq!(err.to_string(), "value `256` doesn't fit in 8 bits");
+    assert!(Flags::try_from(-1i64).unwrap_err().is_out_of_range());
+
+    let err = Flags::try_from(0x9i64).unwrap_err();
+    assert!(!err.is_out_of_range());
+    assert_eq!(err.stray(), 0x8);
+    assert_eq!(err.to_string(), "value `0x9` has unknown bits `0x8`");
+    assert_eq!(Flags::try_from_i64(0x9).unwrap_err().stray(), 0x8);
+
+    #[repr(i64)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Wide{
+        None = 0,
+        A = 1,
+        B = 2
+    }
+
+    assert_eq!(Wide::try_from_i64(3), Ok(Wide::A | Wide::B));
+    assert_eq!(Wide::try_from_i64(5).unwrap_err().stray(), 0x4);
+}
+
+
+#[test]
+fn test_apply_delta(){
+    use enum_flags::FlagDelta;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4,
+        D = 8
+    }
+
+    let original = Flags::A | Flags::B;
+    let mut e1 = original;
+    let delta = FlagDelta::new(Flags::B | Flags::C, Flags::A | Flags::D);
+    let undo = e1.invert_delta(&delta);
+    e1.apply(delta);
+    assert_eq!(e1, Flags::B | Flags::C);
+    e1.apply(undo);
+    assert_eq!(e1, original);
+
+    // a flag both set and cleared ends up cleared
+    let mut e2 = Flags::None;
+    let delta = FlagDelta::new(Flags::A, Flags::A);
+    let undo = e2.invert_delta(&delta);
+    e2.apply(delta);
+    assert_eq!(e2, Flags::None);
+    e2.apply(undo);
+    assert_eq!(e2, Flags::None);
+}
+
+
+#[test]
+fn test_next_prev(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::A.next(), Flags::B);
+    assert_eq!(Flags::C.next(), Flags::A);
+    assert_eq!(Flags::A.prev(), Flags::C);
+    assert_eq!(Flags::B.prev(), Flags::A);
+    assert_eq!(Flags::None.next(), Flags::A);
+    assert_eq!((Flags::A | Flags::B).next(), Flags::A);
+    assert_eq!(Flags::None.prev(), Flags::C);
+}
+
+
+#[test]
+fn test_with_mask(){
+    #[repr(u8)]
+    #[enum_flags(with_mask)]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mask = FlagsMask::new(Flags::A | Flags::B);
+    assert_eq!(mask.bits(), 3);
+    assert_eq!(mask.apply(Flags::A | Flags::C), Flags::A);
+    assert_eq!((!mask).apply(Flags::A | Flags::C), Flags::C);
+    assert_eq!((mask | FlagsMask::from(Flags::C)).apply(Flags::ALL), Flags::ALL);
+    assert_eq!((mask & FlagsMask::from(Flags::B)).apply(Flags::ALL), Flags::B);
+    assert_eq!(FlagsMask::default().apply(Flags::ALL), Flags::None);
+}
+
+
+#[test]
+fn test_eq_ref(){
+    #[repr(u8)]
+    #[enum_flags(no_copy)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let flags = [Flags::A, Flags::B, Flags::A | Flags::C];
+    assert_eq!(flags.iter().position(|flag| flag == Flags::B), Some(1));
+    let first = flags.first().unwrap();
+    assert!(Flags::A == first);
+    assert!(first != Flags::B);
+}
+
+
+#[test]
+fn test_has_stray_bits(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert!(!(Flags::A | Flags::C).has_stray_bits());
+    assert!(Flags::from_bits_retain(0x11).has_stray_bits());
+    (Flags::A | Flags::C).debug_assert_valid();
+}
+
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Flags has stray bits 0x10")]
+fn test_debug_assert_valid(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    Flags::from_bits_retain(0x11).debug_assert_valid();
+}
+
+
+#[test]
+fn test_raw(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let raw = (Flags::A | Flags::C).into_raw();
+    assert_eq!(raw, 5);
+    assert_eq!(unsafe { Flags::from_raw(raw) }, Flags::A | Flags::C);
+    assert!(unsafe { Flags::from_raw(0x10) }.has_stray_bits());
+}
+
+
+#[test]
+fn test_from_str_messy(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::B;
+    assert_eq!("A | A | B".parse::<Flags>(), Ok(e1));
+    assert_eq!("| A | B |".parse::<Flags>(), Ok(e1));
+    assert_eq!("A || B".parse::<Flags>(), Ok(e1));
+    assert_eq!("  A |\tB  ".parse::<Flags>(), Ok(e1));
+    assert_eq!(" | ".parse::<Flags>(), Ok(Flags::None));
+    assert_eq!(Flags::parse_csv(",A,,B,A,"), Ok(e1));
+    assert_eq!(Flags::parse_csv(" , "), Ok(Flags::None));
+    assert!("A | | D".parse::<Flags>().is_err());
+}
+
+
+#[test]
+fn test_union_all(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let items = [Flags::A | Flags::B, Flags::B | Flags::C, Flags::B];
+    assert_eq!(Flags::union_all(&items), Flags::ALL);
+    assert_eq!(Flags::intersection_all(&items), Flags::B);
+    assert_eq!(Flags::union_all(&[]), Flags::None);
+    assert_eq!(Flags::intersection_all(&[]), Flags::ALL);
+}
+
+
+#[test]
+fn test_bytes(){
+    #[repr(u16)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 0x100
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(e1.to_le_bytes(), [0x01, 0x01]);
+    assert_eq!((Flags::B | Flags::C).to_be_bytes(), [0x01, 0x02]);
+    assert_eq!(Flags::from_le_bytes([0x02, 0x01]), Flags::B | Flags::C);
+    assert_eq!(Flags::from_be_bytes(e1.to_be_bytes()), e1);
+}
+
+
+#[test]
+fn test_debug_path(){
+    mod net {
+        use enum_flags::enum_flags;
+
+        #[repr(u8)]
+        #[enum_flags(debug_path)]
+        #[derive(Copy, Clone, PartialEq)]
+        pub enum Flags{
+            None = 0,
+            A = 1,
+            B = 2
+        }
+    }
+    use net::Flags;
+
+    assert_eq!(format!("{:?}", Flags::A | Flags::B), "(test_flags::net::Flags::A | test_flags::net::Flags::B)");
+    assert_eq!(format!("{:?}", Flags::None), "(test_flags::net::Flags::None)");
+    assert_eq!(format!("{:#?}", Flags::B), "(test_flags::net::Flags::B)");
+}
+
+
+#[test]
+fn test_replace(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4,
+        D = 8
+    }
+
+    let mut e1 = Flags::A | Flags::C;
+    e1.replace(Flags::A, Flags::B);
+    assert_eq!(e1, Flags::B | Flags::C);
+
+    // for disjoint sets, replacing is the same as inserting then removing
+    let mut e2 = Flags::A | Flags::C;
+    e2.insert(Flags::B | Flags::D);
+    e2.remove(Flags::A);
+    let mut e3 = Flags::A | Flags::C;
+    e3.replace(Flags::A, Flags::B | Flags::D);
+    assert_eq!(e2, e3);
+
+    // overlapping flags stay set
+    let mut e4 = Flags::A | Flags::B;
+    e4.replace(Flags::A | Flags::B, Flags::B);
+    assert_eq!(e4, Flags::B);
+}
+
+
+#[test]
+fn test_fits_in_bits(){
+    #[repr(u16)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 0x80,
+        C = 0x100,
+        D = 0x8000
+    }
+
+    assert!(Flags::None.fits_in_bits(0));
+    assert!(!Flags::A.fits_in_bits(0));
+    assert!(Flags::A.fits_in_bits(1));
+    assert!((Flags::A | Flags::B).fits_in_bits(8));
+    assert!(!(Flags::A | Flags::C).fits_in_bits(8));
+    assert!((Flags::A | Flags::C).fits_in_bits(9));
+    assert!(!Flags::D.fits_in_bits(15));
+    assert!(Flags::D.fits_in_bits(16));
+    assert!(Flags::D.fits_in_bits(64));
+}
+
+
+#[test]
+fn test_iter_all_bits(){
+    use enum_flags::BitEntry;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::from_num(0x85);
+    let entries: Vec<_> = e1.iter_all_bits().collect();
+    assert_eq!(entries, [BitEntry::Named(Flags::A), BitEntry::Named(Flags::C), BitEntry::Unknown(7)]);
+    assert_eq!(Flags::None.iter_all_bits().count(), 0);
+    assert_eq!(Flags::B.iter_all_bits().collect::<Vec<_>>(), [BitEntry::Named(Flags::B)]);
+}
+
+
+#[test]
+fn test_group(){
+    #[repr(u8)]
+    #[enum_flags(case_insensitive, group(io = [Read, Write], all_rw = [Read, Write, Exec]))]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        Read = 1,
+        Write = 2,
+        Exec = 4
+    }
+
+    assert_eq!(Flags::IO, Flags::Read | Flags::Write);
+    assert_eq!(Flags::ALL_RW, Flags::ALL);
+    assert!(Flags::IO.contains(Flags::Write));
+    assert_eq!(Flags::COUNT, 3);
+}
+
+
+#[test]
+fn test_for_each_flag(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut seen = Flags::None;
+    let mut count = 0;
+    (Flags::A | Flags::C).for_each_flag(|flag| {
+        seen |= flag;
+        count += 1;
+    });
+    assert_eq!(seen, Flags::A | Flags::C);
+    assert_eq!(count, 2);
+
+    Flags::None.for_each_flag(|_| unreachable!());
+}
+
+
+#[test]
+fn test_from_widening(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 0x80
+    }
+
+    #[repr(i8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Signed{
+        None = 0,
+        A = 1,
+        B = 0x40
+    }
+
+    assert_eq!(u64::from(Flags::A | Flags::B), 0x81);
+    assert_eq!(i64::from(Flags::A | Flags::B), 0x81);
+    assert_eq!(u64::from(Flags::None), 0);
+    assert_eq!(u64::from(Signed::B), 0x40);
+    assert_eq!(i64::from(Signed::from_num(-128)), 0x80);
+
+    #[repr(u64)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Wide{
+        None = 0,
+        A = 1,
+        B = 1 << 63
+    }
+
+    // no `From<Wide> for i64`, the top bit would be the sign
+    assert_eq!(u64::from(Wide::A | Wide::B), 0x8000_0000_0000_0001);
+}
+
+
+#[test]
+fn test_try_single(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!(Flags::B.try_single(), Ok(Flags::B));
+    assert_eq!(Flags::from_num(0x82).try_single(), Ok(Flags::B));
+    assert_eq!(Flags::None.try_single().unwrap_err().count(), 0);
+    assert_eq!((Flags::A | Flags::C).try_single().unwrap_err().count(), 2);
+    assert_eq!(Flags::ALL.try_single().unwrap_err().to_string(), "expected a single named flag, found 3");
+}
+
+
+#[test]
+fn test_rename(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        #[flag(rename = "none")]
+        None = 0,
+        #[flag(rename = "read-only")]
+        ReadOnly = 1,
+        Hidden = 2
+    }
+
+    assert_eq!(Flags::ReadOnly.name(), Some("read-only"));
+    assert_eq!(Flags::from_name("read-only"), Some(Flags::ReadOnly));
+    assert_eq!(Flags::from_name("ReadOnly"), None);
+    assert_eq!(Flags::names(), &["read-only", "Hidden"]);
+
+    let e1 = Flags::ReadOnly | Flags::Hidden;
+    assert_eq!(e1.to_string(), "read-only | Hidden");
+    assert_eq!(Flags::None.to_string(), "none");
+    assert_eq!("read-only | Hidden".parse::<Flags>().unwrap(), e1);
+    assert_eq!("none".parse::<Flags>().unwrap(), Flags::None);
+    assert_eq!(format!("{:?}", e1), "(Flags::ReadOnly | Flags::Hidden)");
+}
+
+
+#[test]
+fn test_contains_const(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    const DEFAULTS: Flags = Flags::from_bits_retain(5);
+    const _: () = assert!(DEFAULTS.contains_const(Flags::A));
+    const _: () = assert!(DEFAULTS.contains_const(Flags::None));
+    const _: () = assert!(!DEFAULTS.contains_const(Flags::from_bits_retain(3)));
+    assert_eq!(DEFAULTS.contains_const(Flags::A | Flags::C), DEFAULTS.contains(Flags::A | Flags::C));
+}
+
+
+#[test]
+fn test_parse_ops(){
+    #[repr(u8)]
+    #[enum_flags(parse_ops, display_all = "ALL")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!("!C".parse::<Flags>().unwrap(), Flags::A | Flags::B);
+    assert_eq!("A & !B".parse::<Flags>().unwrap(), Flags::A);
+    assert_eq!("ALL & !C".parse::<Flags>().unwrap(), Flags::A | Flags::B);
+    assert_eq!("ALL - B".parse::<Flags>().unwrap(), Flags::A | Flags::C);
+    assert_eq!("A | B & B | C".parse::<Flags>().unwrap(), Flags::B | Flags::C);
+    assert_eq!("A | C".parse::<Flags>().unwrap(), Flags::A | Flags::C);
+    assert_eq!("5".parse::<Flags>().unwrap(), Flags::A | Flags::C);
+    assert!("!D".parse::<Flags>().is_err());
+    #[cfg(feature = "alloc")]
+    assert_eq!("!D".parse::<Flags>().unwrap_err().token(), "D");
+    assert!("A & !".parse::<Flags>().is_err());
+
+    #[repr(u8)]
+    #[enum_flags(parse_ops)]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Plain{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    assert_eq!("ALL & !C".parse::<Plain>().unwrap(), Plain::A | Plain::B);
+    assert_eq!("ALL - A".parse::<Plain>().unwrap(), Plain::B | Plain::C);
+    assert!("All".parse::<Plain>().is_err());
+}
+
+
+#[test]
+fn test_raw_assign(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A;
+    e1 |= 0x4;
+    assert_eq!(e1, Flags::A | Flags::C);
+    e1 &= 0x6;
+    assert_eq!(e1, Flags::C);
+    e1 ^= 0x6;
+    assert_eq!(e1, Flags::B);
+    e1 |= 0x8;
+    assert_eq!(e1.as_num(), 0xa);
+    assert!(e1.has_stray_bits());
+}
+
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_describe(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let report = Flags::from_num(0x85).describe();
+    assert_eq!(report.set, [Flags::A, Flags::C]);
+    assert_eq!(report.unset, [Flags::B]);
+    assert_eq!(report.stray_bits, 0x80);
+
+    let report = Flags::None.describe();
+    assert!(report.set.is_empty());
+    assert_eq!(report.unset, Flags::variants());
+    assert_eq!(report.stray_bits, 0);
+}
+
+
+#[test]
+fn test_const_discriminants(){
+    const BIT_A: u8 = 1;
+    const BIT_B: u8 = 2;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = BIT_A,
+        B = BIT_B,
+        C = BIT_B << 1,
+        D = 8
+    }
+
+    assert_eq!(Flags::from_num(BIT_A), Flags::A);
+    assert_eq!(Flags::from_num(4), Flags::C);
+    assert_eq!(Flags::from_num(8), Flags::D);
+    assert_eq!((Flags::A | Flags::C).as_num(), 5);
+    assert_eq!(Flags::MASK, 0xf);
+    assert_eq!(format!("{:?}", Flags::B | Flags::C), "(Flags::B | Flags::C)");
+    assert_eq!("A | C".parse::<Flags>().unwrap(), Flags::from_num(5));
+}
+
+
+#[test]
+fn test_const_zero_discriminant(){
+    const EMPTY: u8 = 0;
+    const BIT_A: u8 = 1;
+
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        A = BIT_A,
+        Empty = EMPTY,
+        B = 2,
+        C = BIT_A << 2
+    }
+
+    assert_eq!(Flags::COUNT, 3);
+    assert_eq!(Flags::variants(), [Flags::A, Flags::B, Flags::C]);
+    assert_eq!(Flags::names(), ["A", "B", "C"]);
+    assert_eq!(Flags::FLAGS.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["A", "B", "C"]);
+    assert_eq!(format!("{}", Flags::Empty), "Empty");
+    assert_eq!(format!("{:?}", Flags::Empty), "(Flags::Empty)");
+    assert_eq!(format!("{:?}", Flags::A | Flags::C), "(Flags::A | Flags::C)");
+    assert_eq!(format!("{}", Flags::from_num(0x10)), "");
+    assert!(Flags::Empty.has_empty());
+    assert!(!Flags::A.has_empty());
+    assert!(Flags::A.has_a());
+    assert!(!Flags::B.has_a());
+}
+
+
+#[test]
+fn test_invert_known(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(e1.invert_known(), Flags::B);
+    assert_eq!(e1.invert_known().invert_known(), e1);
+    assert_eq!(Flags::None.invert_known(), Flags::ALL);
+    assert_eq!(Flags::from_num(0x81).invert_known(), Flags::B | Flags::C);
+    assert_eq!((!Flags::from_num(0x81)).as_num(), 0x7e);
+
+    let mut e2 = e1;
+    e2.toggle_all();
+    assert_eq!(e2, Flags::B);
+    e2.toggle_all();
+    assert_eq!(e2, e1);
+}
+
+
+#[test]
+fn test_copy(){
+    fn assert_copy<T: Copy>() {}
+
+    #[repr(u8)]
+    #[enum_flags]
+    enum Small{
+        None = 0,
+        A = 1
+    }
+
+    #[repr(u128)]
+    #[enum_flags]
+    enum Wide{
+        None = 0,
+        A = 1,
+        B = 1 << 127
+    }
+
+    #[enum_flags]
+    enum Word{
+        None = 0,
+        A = 1
+    }
+
+    assert_copy::<Small>();
+    assert_copy::<Wide>();
+    assert_copy::<Word>();
+
+    let e1 = Wide::A | Wide::B;
+    let e2 = e1;
+    assert_eq!(e1, e2);
+}
+
+
+#[test]
+fn test_modify(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A;
+    assert!(e1.modify(Flags::A).is_set());
+    assert!(!e1.modify(Flags::C).is_set());
+    e1.modify(Flags::C).set();
+    assert_eq!(e1, Flags::A | Flags::C);
+    e1.modify(Flags::A).clear().toggle().toggle();
+    assert_eq!(e1, Flags::C);
+
+    let mut entry = e1.modify(Flags::B | Flags::C);
+    assert!(!entry.is_set());
+    entry.toggle();
+    assert!(!entry.is_set());
+    entry.set();
+    assert!(entry.is_set());
+    assert_eq!(e1, Flags::B | Flags::C);
+}
+
+
+#[test]
+fn test_symmetric_difference_known(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::from_num(0x81);
+    let e2 = Flags::A | Flags::C;
+    assert_eq!(e1.symmetric_difference_known(e2), Flags::C);
+    assert_eq!(e1.symmetric_difference(e2).as_num(), 0x84);
+    assert_eq!(e2.symmetric_difference_known(Flags::B), Flags::ALL);
+    assert_eq!(e2.symmetric_difference_known(e2), Flags::None);
+}
+
+
+#[test]
+fn test_from_fn(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let settings = [("A", true), ("B", false), ("C", true)]
+        .iter()
+        .copied()
+        .collect::<std::collections::HashMap<_, _>>();
+    let e1 = Flags::from_fn(|flag| settings.get(flag.name().unwrap()).copied().unwrap_or(false));
+    assert_eq!(e1, Flags::A | Flags::C);
+
+    let mut seen = Vec::new();
+    assert_eq!(Flags::from_fn(|flag| { seen.push(flag); false }), Flags::None);
+    assert_eq!(seen, Flags::variants());
+}
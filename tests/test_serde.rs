@@ -0,0 +1,114 @@
+#![cfg(feature = "serde")]
+
+use enum_flags::enum_flags;
+
+
+#[test]
+fn test_serde_bits(){
+    #[repr(u8)]
+    #[enum_flags(serde)]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(serde_json::to_string(&e1).unwrap(), "5");
+    assert_eq!(serde_json::from_str::<Flags>("5").unwrap(), e1);
+}
+
+#[test]
+fn test_serde_names(){
+    #[repr(u8)]
+    #[enum_flags(serde = "names")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(serde_json::to_string(&e1).unwrap(), r#"["A","C"]"#);
+    assert_eq!(serde_json::from_str::<Flags>(r#"["C","A"]"#).unwrap(), e1);
+    assert!(serde_json::from_str::<Flags>(r#"["A","D"]"#).is_err());
+}
+
+#[test]
+fn test_serde_map(){
+    #[repr(u8)]
+    #[enum_flags(serde = "map")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(serde_json::to_string(&e1).unwrap(), r#"{"A":true,"B":false,"C":true}"#);
+    assert_eq!(serde_json::from_str::<Flags>(r#"{"A":true,"B":false,"C":true}"#).unwrap(), e1);
+    assert_eq!(serde_json::from_str::<Flags>(r#"{"C":true}"#).unwrap(), Flags::C);
+    assert_eq!(serde_json::from_str::<Flags>("{}").unwrap(), Flags::None);
+    assert!(serde_json::from_str::<Flags>(r#"{"A":true,"D":true}"#).is_err());
+}
+
+#[test]
+fn test_serde_case_insensitive(){
+    #[repr(u8)]
+    #[enum_flags(serde = "names", case_insensitive)]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        Read = 1,
+        Write = 2,
+    }
+
+    let e1 = Flags::Read | Flags::Write;
+    assert_eq!(serde_json::from_str::<Flags>(r#"["read","WRITE"]"#).unwrap(), e1);
+    assert_eq!(serde_json::to_string(&e1).unwrap(), r#"["Read","Write"]"#);
+}
+
+#[test]
+fn test_serde_flexible(){
+    #[repr(u8)]
+    #[enum_flags(serde = "flexible")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let e1 = Flags::A | Flags::C;
+    assert_eq!(serde_json::to_string(&e1).unwrap(), r#"["A","C"]"#);
+    assert_eq!(serde_json::from_str::<Flags>("5").unwrap(), e1);
+    assert_eq!(serde_json::from_str::<Flags>(r#"["A","C"]"#).unwrap(), e1);
+    assert!(serde_json::from_str::<Flags>("256").is_err());
+    assert!(serde_json::from_str::<Flags>("-1").is_err());
+    assert!(serde_json::from_str::<Flags>(r#""A""#).is_err());
+}
+
+#[test]
+fn test_serde_rename(){
+    #[repr(u8)]
+    #[enum_flags(serde = "names")]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        #[flag(rename = "read-only")]
+        ReadOnly = 1,
+        Hidden = 2
+    }
+
+    let e1 = Flags::ReadOnly | Flags::Hidden;
+    assert_eq!(serde_json::to_string(&e1).unwrap(), r#"["read-only","Hidden"]"#);
+    assert_eq!(serde_json::from_str::<Flags>(r#"["read-only","Hidden"]"#).unwrap(), e1);
+    assert!(serde_json::from_str::<Flags>(r#"["ReadOnly"]"#).is_err());
+}
@@ -0,0 +1,41 @@
+#![cfg(all(feature = "validate", debug_assertions))]
+
+use enum_flags::enum_flags;
+
+
+#[test]
+#[should_panic(expected = "has stray bits")]
+fn test_validate_insert(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A;
+    e1.insert(Flags::from_bits_retain(0x10));
+}
+
+
+#[test]
+fn test_validate_valid(){
+    #[repr(u8)]
+    #[enum_flags]
+    #[derive(Copy, Clone, PartialEq)]
+    enum Flags{
+        None = 0,
+        A = 1,
+        B = 2,
+        C = 4
+    }
+
+    let mut e1 = Flags::A;
+    e1.insert(Flags::C);
+    e1.toggle(Flags::A | Flags::B);
+    e1 -= Flags::B;
+    assert_eq!(e1, Flags::C);
+}
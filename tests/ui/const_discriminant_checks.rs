@@ -0,0 +1,30 @@
+use enum_flags::enum_flags;
+
+const ONE: u8 = 1;
+const BIT_C: u8 = 4;
+
+#[repr(u8)]
+#[enum_flags]
+enum NonzeroNone {
+    None = ONE,
+    A = 2,
+}
+
+#[repr(u8)]
+#[enum_flags(ordered)]
+enum Composite {
+    None = 0,
+    A = 1,
+    AC = ONE | BIT_C,
+}
+
+#[repr(u8)]
+#[enum_flags(ordered)]
+enum Descending {
+    None = 0,
+    A = 1,
+    C = BIT_C,
+    B = 2,
+}
+
+fn main() {}
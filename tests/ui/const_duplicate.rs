@@ -0,0 +1,13 @@
+use enum_flags::enum_flags;
+
+const BIT_A: u8 = 1;
+
+#[repr(u8)]
+#[enum_flags]
+enum Flags {
+    None = 0,
+    A = BIT_A,
+    B = 1,
+}
+
+fn main() {}
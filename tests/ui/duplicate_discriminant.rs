@@ -0,0 +1,11 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+enum Flags {
+    None = 0,
+    A = 1,
+    B = 2,
+    C = 1,
+}
+
+fn main() {}
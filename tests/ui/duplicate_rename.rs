@@ -0,0 +1,11 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+enum Flags {
+    None = 0,
+    #[flag(rename = "B")]
+    A = 1,
+    B = 2,
+}
+
+fn main() {}
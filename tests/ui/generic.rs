@@ -0,0 +1,15 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+enum Flags<T> {
+    None = 0,
+    A = 1,
+}
+
+#[enum_flags]
+enum Bounded where u8: Copy {
+    None = 0,
+    A = 1,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use enum_flags::enum_flags;
+
+#[enum_flags(group(all = [Read, Write]))]
+enum Generated {
+    None = 0,
+    Read = 1,
+    Write = 2,
+}
+
+#[enum_flags(group(rw = [Read, Write]))]
+enum Variant {
+    None = 0,
+    Read = 1,
+    Write = 2,
+    RW = 3,
+}
+
+#[enum_flags(group(io = [Read], IO = [Write]))]
+enum Group {
+    None = 0,
+    Read = 1,
+    Write = 2,
+}
+
+fn main() {}
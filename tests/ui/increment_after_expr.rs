@@ -0,0 +1,11 @@
+use enum_flags::enum_flags;
+
+const BIT_A: usize = 1;
+
+#[enum_flags]
+enum Flags {
+    A = BIT_A,
+    B,
+}
+
+fn main() {}
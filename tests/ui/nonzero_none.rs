@@ -0,0 +1,9 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+enum Flags {
+    None = 1,
+    A = 2,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use enum_flags::enum_flags;
+
+#[enum_flags(accessor_kind = "option")]
+enum Flags {
+    None = 0,
+    A = 1,
+    Set = 2,
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+use enum_flags::enum_flags;
+
+#[repr(u8)]
+#[enum_flags(ordered)]
+enum Descending {
+    None = 0,
+    A = 1,
+    C = 4,
+    B = 2,
+}
+
+#[repr(u8)]
+#[enum_flags(ordered)]
+enum Composite {
+    None = 0,
+    A = 1,
+    B = 2,
+    AB = 3,
+}
+
+#[repr(u8)]
+#[enum_flags(ordered)]
+enum ZeroBetween {
+    A = 4,
+    None = 0,
+    B = 2,
+}
+
+#[repr(u8)]
+#[enum_flags(ordered)]
+enum Ascending {
+    None = 0,
+    A = 1,
+    B = 2,
+    C = 0x80,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use enum_flags::enum_flags;
+
+#[enum_flags(parse_ops)]
+enum Flags {
+    None = 0,
+    #[flag(rename = "read-only")]
+    ReadOnly = 1,
+}
+
+fn main() {}
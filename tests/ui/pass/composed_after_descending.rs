@@ -0,0 +1,21 @@
+use enum_flags::enum_flags;
+
+#[repr(u8)]
+#[enum_flags]
+enum F {
+    A = 4,
+    B = 1,
+}
+
+#[repr(u8)]
+#[enum_flags]
+enum G {
+    None = 0,
+    A = 2,
+    B = 1,
+}
+
+fn main() {
+    assert_eq!((F::A | F::B).as_num(), 5);
+    assert_eq!((G::A | G::B).as_num(), 3);
+}
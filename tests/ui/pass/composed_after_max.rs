@@ -0,0 +1,14 @@
+use enum_flags::enum_flags;
+
+#[repr(u8)]
+#[enum_flags]
+enum F {
+    None = 0,
+    A = 1,
+    B = 2,
+    All = 0xFF,
+}
+
+fn main() {
+    assert_eq!((F::A | F::B).as_num(), 3);
+}
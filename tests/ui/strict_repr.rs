@@ -0,0 +1,25 @@
+use enum_flags::enum_flags;
+
+#[repr(u32)]
+#[enum_flags(strict_repr)]
+enum Flags {
+    None = 0,
+    A = 1,
+    B = 0x100,
+}
+
+#[enum_flags(strict_repr)]
+enum Implicit {
+    None = 0,
+    A = 1,
+}
+
+#[repr(u16)]
+#[enum_flags(strict_repr)]
+enum Fits {
+    None = 0,
+    A = 1,
+    B = 0x100,
+}
+
+fn main() {}
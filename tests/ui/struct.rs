@@ -0,0 +1,8 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+struct Flags {
+    bits: u32,
+}
+
+fn main() {}
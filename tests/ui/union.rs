@@ -0,0 +1,8 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+union Flags {
+    bits: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use enum_flags::enum_flags;
+
+#[enum_flags(default = "A | D")]
+enum Flags {
+    None = 0,
+    A = 1,
+    B = 2,
+}
+
+fn main() {}
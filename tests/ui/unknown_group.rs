@@ -0,0 +1,10 @@
+use enum_flags::enum_flags;
+
+#[enum_flags(group(io = [Read, Exec]))]
+enum Flags {
+    None = 0,
+    Read = 1,
+    Write = 2,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_flags::enum_flags;
+
+#[enum_flags(no_such_option)]
+enum Flags {
+    None = 0,
+    A = 1,
+}
+
+fn main() {}
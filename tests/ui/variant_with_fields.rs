@@ -0,0 +1,10 @@
+use enum_flags::enum_flags;
+
+#[enum_flags]
+enum Flags {
+    None = 0,
+    A(u32),
+    B = 2,
+}
+
+fn main() {}